@@ -0,0 +1,209 @@
+//! Actor flags, `<Map>_<Actor>_<hash id>`
+//!
+//! Placed actors that remember their state, an opened chest or a defeated
+//! enemy, have a flag named after the map, the actor and the hash id of
+//! the placement, e.g. `MainField_Enemy_Lynel_Junior_3006291226`.  Actors
+//! are grouped in classes, e.g. ore deposits or treasure chests, by the
+//! actor name patterns in `CLASSES`.
+
+use std::sync::OnceLock;
+
+use wildmatch::WildMatch;
+
+use crate::hash::KEYS;
+use crate::SaveData;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Map {
+    MainField,
+    AocField,
+    /// Shrines
+    CDungeon,
+    /// Hyrule Castle
+    MainFieldDungeon,
+    MarioClubTestDungeon,
+}
+
+const MAPS: [(Map, &str); 5] = [
+    (Map::MainField, "MainField"),
+    (Map::AocField, "AocField"),
+    (Map::CDungeon, "CDungeon"),
+    (Map::MainFieldDungeon, "MainFieldDungeon"),
+    (Map::MarioClubTestDungeon, "MarioClubTestDungeon"),
+];
+
+impl Map {
+    pub fn name(&self) -> &'static str {
+        MAPS.iter().find(|m| m.0 == *self).unwrap().1
+    }
+    pub fn from_name(name: &str) -> Option<Map> {
+        MAPS.iter().find(|m| m.1 == name).map(|m| m.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Class {
+    OreDeposit,
+    TreasureChest,
+    KorokSeed,
+    Enemy,
+    Weapon,
+    Material,
+    Animal,
+    Crate,
+    Barrel,
+    Arrow,
+    Rupee,
+}
+
+/// Class, name and actor name patterns
+const CLASSES: [(Class, &str, &[&str]); 11] = [
+    (Class::OreDeposit, "ore_deposits", &["Obj_Mineral*"]),
+    (Class::TreasureChest, "treasure_chests", &["TBox_*"]),
+    (Class::KorokSeed, "korok_seeds", &["Npc_HiddenKorok*"]),
+    (Class::Enemy, "enemies", &["Enemy_*"]),
+    (Class::Weapon, "weapons", &["Weapon_*"]),
+    (Class::Material, "materials", &["Item_*"]),
+    (Class::Animal, "animals", &["Animal_*"]),
+    (Class::Crate, "crates", &["Kibako*", "Obj_BreakBoxIron*"]),
+    (Class::Barrel, "barrels", &["Barrel*"]),
+    (Class::Arrow, "arrows", &["Obj_*Arrow*"]),
+    (Class::Rupee, "rupees", &["PutRupee*"]),
+];
+
+impl Class {
+    pub fn name(&self) -> &'static str {
+        CLASSES.iter().find(|c| c.0 == *self).unwrap().1
+    }
+    pub fn from_name(name: &str) -> Option<Class> {
+        CLASSES
+            .iter()
+            .find(|c| c.1.eq_ignore_ascii_case(name))
+            .map(|c| c.0)
+    }
+
+    /// Class of an actor name, e.g. `Obj_Mineral_A_01`
+    pub fn of_actor(actor: &str) -> Option<Class> {
+        static PATTERNS: OnceLock<Vec<(Class, WildMatch)>> = OnceLock::new();
+        PATTERNS
+            .get_or_init(|| {
+                CLASSES
+                    .iter()
+                    .flat_map(|(class, _, patterns)| {
+                        patterns.iter().map(|p| (*class, WildMatch::new(p)))
+                    })
+                    .collect()
+            })
+            .iter()
+            .find(|(_, re)| re.matches(actor))
+            .map(|(class, _)| *class)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActorFlag {
+    pub map: Map,
+    pub actor: String,
+    pub hash_id: u32,
+}
+
+impl ActorFlag {
+    /// Parse a flag name, None for flags of no placed actor
+    pub fn parse(flag: &str) -> Option<ActorFlag> {
+        let (map, rest) = flag.split_once('_')?;
+        let (actor, id) = rest.rsplit_once('_')?;
+        if actor.is_empty() {
+            return None;
+        }
+        Some(ActorFlag {
+            map: Map::from_name(map)?,
+            actor: actor.to_string(),
+            hash_id: id.parse().ok()?,
+        })
+    }
+
+    pub fn flag(&self) -> String {
+        format!("{}_{}_{}", self.map.name(), self.actor, self.hash_id)
+    }
+
+    /// Actor class, None for actors in no class
+    pub fn class(&self) -> Option<Class> {
+        Class::of_actor(&self.actor)
+    }
+}
+
+/// Every actor flag, parsed once
+pub fn flags() -> &'static [ActorFlag] {
+    static FLAGS: OnceLock<Vec<ActorFlag>> = OnceLock::new();
+    FLAGS.get_or_init(|| KEYS.iter().filter_map(|k| ActorFlag::parse(k)).collect())
+}
+
+/// Actor flags of a class, e.g. `Class::TreasureChest`
+pub fn of_class(class: Class) -> Vec<&'static ActorFlag> {
+    flags()
+        .iter()
+        .filter(|f| f.class() == Some(class))
+        .collect()
+}
+
+/// Actor flags with actor names matching `pattern`, e.g. `Enemy_Lynel_*`
+pub fn matching(pattern: &str) -> Vec<&'static ActorFlag> {
+    let re = WildMatch::new(pattern);
+    flags().iter().filter(|f| re.matches(&f.actor)).collect()
+}
+
+impl SaveData {
+    /// Actor flags of a class in the save with their values
+    pub fn actor_flags(&self, class: Class) -> Vec<(&'static ActorFlag, bool)> {
+        of_class(class)
+            .into_iter()
+            .filter_map(|f| {
+                let value = self.get_bool(&f.flag())?;
+                Some((f, value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_query() {
+        let f = ActorFlag::parse("MainField_Enemy_Giant_Junior_3337886442").unwrap();
+        assert_eq!(f.map, Map::MainField);
+        assert_eq!(f.actor, "Enemy_Giant_Junior");
+        assert_eq!(f.hash_id, 3337886442);
+        assert_eq!(f.class(), Some(Class::Enemy));
+        assert_eq!(f.flag(), "MainField_Enemy_Giant_Junior_3337886442");
+        let f = ActorFlag::parse("MainFieldDungeon_TBox_Dungeon_Iron_1051960528").unwrap();
+        assert_eq!(
+            (f.map, f.class()),
+            (Map::MainFieldDungeon, Some(Class::TreasureChest))
+        );
+        assert_eq!(Class::from_name("Ore_Deposits"), Some(Class::OreDeposit));
+        assert_eq!(
+            Class::of_actor("Obj_MineralBury_A_01"),
+            Some(Class::OreDeposit)
+        );
+        assert_eq!(Class::of_actor("DgnObj_DungeonEntranceSP"), None);
+        for bad in ["Location_Dungeon018", "MainField_Enemy_x", "MainField_1234"] {
+            assert_eq!(ActorFlag::parse(bad), None);
+        }
+
+        let all = flags();
+        assert_eq!(all.iter().filter(|f| f.map == Map::CDungeon).count(), 391);
+        assert_eq!(of_class(Class::KorokSeed).len(), crate::korok::TOTAL);
+        let lynels = matching("Enemy_Lynel_*");
+        assert!(!lynels.is_empty());
+        assert!(lynels.iter().all(|f| f.actor.starts_with("Enemy_Lynel_")));
+
+        let s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let chests = s.actor_flags(Class::TreasureChest);
+        assert!(chests.len() > 1000);
+        assert!(chests
+            .iter()
+            .any(|(f, v)| *v && f.actor == "TBox_Field_Stone" && f.hash_id == 4129291175));
+    }
+}
@@ -0,0 +1,259 @@
+//! Album photos, `album/pict_NNN.jpg` joined with the slot's arrays
+//!
+//! Photo `i` is described by entry `i` of `AlbumPictureActorName`,
+//! `AlbumPictureSize`, `AlbumSaveLocationName` and `AlbumSaveDistrictName`.
+//! The jpg files are padded to 64 KiB, the size array holds the length of
+//! the actual image.
+
+use crate::folder::SaveFolder;
+use crate::SaveData;
+
+const ACTOR: &str = "AlbumPictureActorName";
+const SIZE: &str = "AlbumPictureSize";
+const LOCATION: &str = "AlbumSaveLocationName";
+const DISTRICT: &str = "AlbumSaveDistrictName";
+const FILE_SIZE: usize = 0x10000;
+
+fn file_name(index: usize) -> String {
+    format!("pict_{:03}.jpg", index)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Photo {
+    /// Subject actor, e.g. `Enemy_Guardian_A`
+    pub actor: String,
+    pub location: String,
+    pub district: String,
+    pub size: i32,
+    /// Padded contents of `pict_NNN.jpg`, if present
+    pub jpg: Option<Vec<u8>>,
+}
+
+impl Photo {
+    /// Image without the padding
+    pub fn image(&self) -> Option<&[u8]> {
+        let jpg = self.jpg.as_ref()?;
+        Some(&jpg[..(self.size.max(0) as usize).min(jpg.len())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Album {
+    pub photos: Vec<Photo>,
+    /// Length of the arrays
+    capacity: usize,
+    /// Slots sharing this album
+    slots: Vec<usize>,
+    /// Files beyond the last photo
+    pub orphans: Vec<String>,
+}
+
+// Actor, size, location and district
+type Arrays = (Vec<String>, Vec<i32>, Vec<String>, Vec<String>);
+
+// Photos recorded in `save`, the arrays end at the first empty entry
+fn photo_count(save: &SaveData) -> usize {
+    match arrays(save) {
+        Some((actor, size, _, _)) => (0..size.len())
+            .take_while(|&i| size[i] > 0 || !actor[i].is_empty())
+            .count(),
+        None => 0,
+    }
+}
+
+fn arrays(save: &SaveData) -> Option<Arrays> {
+    Some((
+        save.get_strs(ACTOR)?,
+        save.get_s32s(SIZE)?,
+        save.get_strs(LOCATION)?,
+        save.get_strs(DISTRICT)?,
+    ))
+}
+
+impl Album {
+    /// Album as recorded in `slot`, along with the other slots that
+    /// record the same album
+    pub fn read(folder: &SaveFolder, slot: usize) -> Result<Album, String> {
+        let save = &folder
+            .slot(slot)
+            .ok_or(format!("Error: slot {} is empty", slot))?
+            .save;
+        let (actor, size, location, district) =
+            arrays(save).ok_or("Error: save has no album arrays".to_string())?;
+        let slots = folder
+            .slots
+            .iter()
+            .filter(|s| {
+                arrays(&s.save)
+                    == Some((
+                        actor.clone(),
+                        size.clone(),
+                        location.clone(),
+                        district.clone(),
+                    ))
+            })
+            .map(|s| s.index)
+            .collect();
+        let mut photos = vec![];
+        for i in 0..photo_count(save) {
+            photos.push(Photo {
+                actor: actor[i].clone(),
+                location: location[i].clone(),
+                district: district[i].clone(),
+                size: size[i],
+                jpg: folder.album.get(&file_name(i)).cloned(),
+            });
+        }
+        let orphans = folder
+            .album
+            .keys()
+            .filter(|name| !(0..photos.len()).any(|i| file_name(i) == **name))
+            .cloned()
+            .collect();
+        Ok(Album {
+            photos,
+            capacity: size.len(),
+            slots,
+            orphans,
+        })
+    }
+
+    /// Photos whose jpg is missing or shorter than the recorded size
+    pub fn missing(&self) -> Vec<usize> {
+        self.photos
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| match &p.jpg {
+                Some(jpg) => jpg.len() < p.size.max(0) as usize,
+                None => true,
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn delete(&mut self, index: usize) -> Result<Photo, String> {
+        if index >= self.photos.len() {
+            return Err(format!("Error: no photo {}", index));
+        }
+        Ok(self.photos.remove(index))
+    }
+
+    /// Move photo `from` to position `to`, shifting the ones in between
+    pub fn reorder(&mut self, from: usize, to: usize) -> Result<(), String> {
+        if from >= self.photos.len() || to >= self.photos.len() {
+            return Err(format!("Error: no photo {}", from.max(to)));
+        }
+        let photo = self.photos.remove(from);
+        self.photos.insert(to, photo);
+        Ok(())
+    }
+
+    /// Write the arrays of every slot sharing the album and renumber the
+    /// jpg files.  Files no slot records are removed; the write is refused
+    /// when it would change a file that a slot with another album records.
+    pub fn write(&self, folder: &mut SaveFolder) -> Result<(), String> {
+        if self.photos.len() > self.capacity {
+            return Err(format!("Error: album holds {} photos", self.capacity));
+        }
+        // Files recorded by the slots that do not share this album
+        let kept = folder
+            .slots
+            .iter()
+            .filter(|s| !self.slots.contains(&s.index))
+            .map(|s| photo_count(&s.save))
+            .max()
+            .unwrap_or(0);
+        let mut actor = vec![String::new(); self.capacity];
+        let mut size = vec![0; self.capacity];
+        let mut location = vec![String::new(); self.capacity];
+        let mut district = vec![String::new(); self.capacity];
+        let mut files = vec![];
+        for (i, p) in self.photos.iter().enumerate() {
+            actor[i] = p.actor.clone();
+            size[i] = p.size;
+            location[i] = p.location.clone();
+            district[i] = p.district.clone();
+            let jpg = p.jpg.clone().map(|mut jpg| {
+                jpg.resize(jpg.len().max(FILE_SIZE), 0);
+                jpg
+            });
+            if i < kept && folder.album.get(&file_name(i)) != jpg.as_ref() {
+                return Err(format!(
+                    "Error: {} is recorded by a slot with another album",
+                    file_name(i)
+                ));
+            }
+            files.push(jpg);
+        }
+        let recorded = kept.max(self.photos.len());
+        folder
+            .album
+            .retain(|name, _| (0..recorded).any(|i| file_name(i) == *name));
+        for (i, jpg) in files.into_iter().enumerate() {
+            match jpg {
+                Some(jpg) => folder.album.insert(file_name(i), jpg),
+                None if i >= kept => folder.album.remove(&file_name(i)),
+                None => None,
+            };
+        }
+        for &index in &self.slots {
+            let save = &mut folder.slot_mut(index).unwrap().save;
+            save.set_strs(ACTOR, &actor)?;
+            save.set_s32s(SIZE, &size)?;
+            save.set_strs(LOCATION, &location)?;
+            save.set_strs(DISTRICT, &district)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_and_reorder() {
+        let mut f = SaveFolder::open("t/+99 cleanup").unwrap();
+        let mut a = Album::read(&f, 1).unwrap();
+        assert_eq!(a.photos.len(), 19);
+        assert_eq!(a.slots, vec![0, 1, 2, 3, 4, 5]);
+        assert!(a.orphans.is_empty() && a.missing().is_empty());
+        assert_eq!(a.photos[1].actor, "Enemy_Guardian_A");
+        assert_eq!(a.photos[0].location, "HatenoLabo");
+        assert_eq!(a.photos[0].image().unwrap().len(), 35485);
+
+        let p = a.delete(0).unwrap();
+        a.reorder(0, 2).unwrap();
+        a.write(&mut f).unwrap();
+        let b = Album::read(&f, 4).unwrap();
+        assert_eq!(b.photos.len(), 18);
+        assert_eq!(b.photos[2].actor, "Enemy_Guardian_A");
+        assert_eq!(b.photos[0].actor, "Enemy_Lynel_Junior");
+        assert!(!b.photos.contains(&p));
+        assert!(b.missing().is_empty());
+        assert!(!f.album.contains_key("pict_018.jpg"));
+
+        // Slot 6 has no photos, writing it keeps the files slots 0 to 5 use
+        let mut f = SaveFolder::open("t/+99 cleanup").unwrap();
+        let a = Album::read(&f, 6).unwrap();
+        assert_eq!((a.photos.len(), a.orphans.len()), (0, 19));
+        a.write(&mut f).unwrap();
+        assert_eq!(f.album.len(), 19);
+
+        // Slot 0 records another album, renumbering its files is refused
+        let save = &mut f.slot_mut(0).unwrap().save;
+        let mut size = save.get_s32s(SIZE).unwrap();
+        size[0] += 1;
+        save.set_s32s(SIZE, &size).unwrap();
+        let mut a = Album::read(&f, 1).unwrap();
+        assert_eq!(a.slots, vec![1, 2, 3, 4, 5]);
+        a.delete(0).unwrap();
+        assert!(a.write(&mut f).is_err());
+        assert!(Album::read(&f, 1).unwrap().write(&mut f).is_ok());
+
+        let f = SaveFolder::open("t/3AA Blights Segment Start v2").unwrap();
+        let a = Album::read(&f, 0).unwrap();
+        assert!(a.photos.is_empty());
+        assert_eq!(a.orphans.len(), 34);
+    }
+}
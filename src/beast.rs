@@ -0,0 +1,324 @@
+//! Divine Beasts and their Blights, the `*_RemainsWater`, `Fire`, `Wind`
+//! and `Electric` flag groups
+//!
+//! ```text
+//! Location_RemainsX, FirstMessage_RemainsX, Enter_RemainsX   boarded
+//! Counter_TerminalX     s32, terminals left to activate, 5 to 0
+//! Access_AllTerminalX   every terminal activated
+//! Appear_PGanonX, Die_PGanonX, Clear_RemainsX   Blight fought and defeated
+//! IsGet_Obj_HeroSoul_<Race>   Champion ability granted
+//! ```
+//!
+//! The beasts' `IsPlayed_Demo*` cutscene flags are numbered, not named.
+//! The saves here have every beast done or none, so over a hundred demo
+//! flags change together and none can be tied to one beast.  `set_phase`
+//! leaves them alone until there is a per-beast list of them; the game
+//! plays a skipped cutscene again when it is reached.
+//!
+//! Puzzle state inside a beast, the `RemainsX_*` doors, switches and small
+//! keys, is put back to a new game's when the beast is set to not entered
+//! and kept otherwise.
+
+use crate::hash::KEYS;
+use crate::SaveData;
+
+pub const TERMINALS: i32 = 5;
+
+/// Puzzle flags a new game starts with set
+const PUZZLE_NEW_GAME: [&str; 1] = ["RemainsElectric_1st"];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Beast {
+    Ruta,
+    Rudania,
+    Medoh,
+    Naboris,
+}
+
+/// Beast, flag suffix and Champion race
+const BEASTS: [(Beast, &str, &str); 4] = [
+    (Beast::Ruta, "Water", "Zora"),
+    (Beast::Rudania, "Fire", "Goron"),
+    (Beast::Medoh, "Wind", "Rito"),
+    (Beast::Naboris, "Electric", "Gerudo"),
+];
+
+impl Beast {
+    pub fn all() -> [Beast; 4] {
+        BEASTS.map(|b| b.0)
+    }
+    fn entry(&self) -> &'static (Beast, &'static str, &'static str) {
+        BEASTS.iter().find(|b| b.0 == *self).unwrap()
+    }
+    /// Flag suffix, e.g. `Water`
+    pub fn element(&self) -> &'static str {
+        self.entry().1
+    }
+    pub fn race(&self) -> &'static str {
+        self.entry().2
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Beast::Ruta => "Ruta",
+            Beast::Rudania => "Rudania",
+            Beast::Medoh => "Medoh",
+            Beast::Naboris => "Naboris",
+        }
+    }
+    /// Beast from its name, element or Champion race, e.g. `Ruta`,
+    /// `VahRuta`, `Water` or `Zora`
+    pub fn from_name(name: &str) -> Option<Beast> {
+        let name = name.strip_prefix("Vah").unwrap_or(name);
+        Beast::all().into_iter().find(|b| {
+            [b.name(), b.element(), b.race()]
+                .iter()
+                .any(|n| n.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    NotEntered,
+    Entered,
+    TerminalsActivated,
+    BlightDefeated,
+    AbilityGranted,
+}
+
+const PHASES: [(Phase, &str); 5] = [
+    (Phase::NotEntered, "not_entered"),
+    (Phase::Entered, "entered"),
+    (Phase::TerminalsActivated, "terminals_activated"),
+    (Phase::BlightDefeated, "blight_defeated"),
+    (Phase::AbilityGranted, "ability_granted"),
+];
+
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        PHASES.iter().find(|p| p.0 == *self).unwrap().1
+    }
+    pub fn from_name(name: &str) -> Option<Phase> {
+        PHASES
+            .iter()
+            .find(|p| p.1.eq_ignore_ascii_case(name))
+            .map(|p| p.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeastState {
+    pub beast: Beast,
+    /// Times the location name was shown
+    pub visits: i32,
+    pub entered: bool,
+    /// Terminals left to activate
+    pub terminals_left: i32,
+    pub all_terminals: bool,
+    pub blight_appeared: bool,
+    pub blight_defeated: bool,
+    pub cleared: bool,
+    pub ability: bool,
+    /// `RemainsX_*` puzzle flags inside the beast
+    pub puzzle: Vec<(&'static str, bool)>,
+    /// `RemainsX_SmallKeyNum`, small keys held
+    pub small_keys: i32,
+}
+
+fn small_keys_flag(e: &str) -> String {
+    format!("Remains{}_SmallKeyNum", e)
+}
+
+/// The `RemainsX_*` puzzle flags of a beast, sorted
+fn puzzle_flags(beast: Beast) -> Vec<&'static str> {
+    let prefix = format!("Remains{}_", beast.element());
+    let keys = small_keys_flag(beast.element());
+    let mut v: Vec<_> = KEYS
+        .iter()
+        .copied()
+        .filter(|k| k.starts_with(&prefix) && *k != keys)
+        .collect();
+    v.sort_unstable();
+    v
+}
+
+impl BeastState {
+    fn flags(&self) -> [(String, bool); 7] {
+        let e = self.beast.element();
+        [
+            (format!("FirstMessage_Remains{}", e), self.entered),
+            (format!("Enter_Remains{}", e), self.entered),
+            (format!("Access_AllTerminal{}", e), self.all_terminals),
+            (format!("Appear_PGanon{}", e), self.blight_appeared),
+            (format!("Die_PGanon{}", e), self.blight_defeated),
+            (format!("Clear_Remains{}", e), self.cleared),
+            (
+                format!("IsGet_Obj_HeroSoul_{}", self.beast.race()),
+                self.ability,
+            ),
+        ]
+    }
+
+    /// Terminals activated so far
+    pub fn terminals(&self) -> i32 {
+        TERMINALS - self.terminals_left
+    }
+
+    pub fn phase(&self) -> Phase {
+        if self.ability {
+            Phase::AbilityGranted
+        } else if self.blight_defeated && self.cleared {
+            Phase::BlightDefeated
+        } else if self.all_terminals {
+            Phase::TerminalsActivated
+        } else if self.entered {
+            Phase::Entered
+        } else {
+            Phase::NotEntered
+        }
+    }
+
+    /// Set the flags of `phase` and every phase before it, clear the rest
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.entered = phase >= Phase::Entered;
+        self.visits = if self.entered { self.visits.max(1) } else { 0 };
+        self.all_terminals = phase >= Phase::TerminalsActivated;
+        self.terminals_left = if self.all_terminals { 0 } else { TERMINALS };
+        if !self.entered {
+            for (key, value) in self.puzzle.iter_mut() {
+                *value = PUZZLE_NEW_GAME.contains(key);
+            }
+            self.small_keys = 0;
+        }
+        self.blight_appeared = phase >= Phase::BlightDefeated;
+        self.blight_defeated = self.blight_appeared;
+        self.cleared = self.blight_appeared;
+        self.ability = phase >= Phase::AbilityGranted;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Beasts {
+    pub beasts: Vec<BeastState>,
+}
+
+impl Beasts {
+    pub fn read(save: &SaveData) -> Result<Beasts, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        let mut beasts = vec![];
+        for beast in Beast::all() {
+            let e = beast.element();
+            let s32 = |k: String| save.get_s32(&k).ok_or(missing(&k));
+            let mut b = BeastState {
+                beast,
+                visits: s32(format!("Location_Remains{}", e))?,
+                entered: false,
+                terminals_left: s32(format!("Counter_Terminal{}", e))?,
+                all_terminals: false,
+                blight_appeared: false,
+                blight_defeated: false,
+                cleared: false,
+                ability: false,
+                puzzle: vec![],
+                small_keys: s32(small_keys_flag(e))?,
+            };
+            let values = b.flags().map(|(k, _)| save.get_bool(&k).ok_or(missing(&k)));
+            let [_, entered, all, appeared, defeated, cleared, ability] = values;
+            b.entered = entered?;
+            b.all_terminals = all?;
+            b.blight_appeared = appeared?;
+            b.blight_defeated = defeated?;
+            b.cleared = cleared?;
+            b.ability = ability?;
+            for key in puzzle_flags(beast) {
+                b.puzzle
+                    .push((key, save.get_bool(key).ok_or(missing(key))?));
+            }
+            beasts.push(b);
+        }
+        Ok(Beasts { beasts })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for b in &self.beasts {
+            let e = b.beast.element();
+            save.set_s32(&format!("Location_Remains{}", e), b.visits)?;
+            save.set_s32(&format!("Counter_Terminal{}", e), b.terminals_left)?;
+            for (key, value) in b.flags() {
+                save.set_bool(&key, value)?;
+            }
+            for (key, value) in &b.puzzle {
+                save.set_bool(key, *value)?;
+            }
+            save.set_s32(&small_keys_flag(e), b.small_keys)?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, beast: Beast) -> &BeastState {
+        self.beasts.iter().find(|b| b.beast == beast).unwrap()
+    }
+
+    pub fn get_mut(&mut self, beast: Beast) -> &mut BeastState {
+        self.beasts.iter_mut().find(|b| b.beast == beast).unwrap()
+    }
+
+    /// Set a phase by names, e.g. `("Medoh", "blight_defeated")`
+    pub fn set_phase(&mut self, beast: &str, phase: &str) -> Result<(), String> {
+        let b = Beast::from_name(beast).ok_or(format!("Error: no divine beast {}", beast))?;
+        let p = Phase::from_name(phase).ok_or(format!("Error: no beast phase {}", phase))?;
+        self.get_mut(b).set_phase(p);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases() {
+        let mut s = SaveData::read("t/3AA Blights Segment Start v2/0/game_data.sav").unwrap();
+        let mut b = Beasts::read(&s).unwrap();
+        assert!(b.beasts.iter().all(|b| b.phase() == Phase::NotEntered));
+        assert_eq!(b.get(Beast::Ruta).terminals(), 0);
+        let done = Beasts::read(&SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap()).unwrap();
+        assert!(done
+            .beasts
+            .iter()
+            .all(|b| b.phase() == Phase::AbilityGranted));
+
+        assert_eq!(Beast::from_name("VahMedoh"), Some(Beast::Medoh));
+        assert_eq!(Beast::from_name("gerudo"), Some(Beast::Naboris));
+        assert!(b.set_phase("Ganon", "entered").is_err());
+        assert!(b.set_phase("Ruta", "done").is_err());
+        b.set_phase("Fire", "blight_defeated").unwrap();
+        b.get_mut(Beast::Naboris).set_phase(Phase::Entered);
+        b.get_mut(Beast::Naboris).terminals_left = 2;
+        b.write(&mut s).unwrap();
+
+        let mut b = Beasts::read(&s).unwrap();
+        assert_eq!(b.get(Beast::Rudania).phase(), Phase::BlightDefeated);
+        assert_eq!(s.get_bool("Die_PGanonFire"), Some(true));
+        assert_eq!(s.get_s32("Counter_TerminalFire"), Some(0));
+        assert_eq!(s.get_bool("IsGet_Obj_HeroSoul_Goron"), Some(false));
+        assert_eq!(b.get(Beast::Naboris).terminals(), 3);
+        b.set_phase("Rudania", "not_entered").unwrap();
+        b.write(&mut s).unwrap();
+        assert_eq!(s.get_s32("Counter_TerminalFire"), Some(5));
+        assert_eq!(s.get_s32("Location_RemainsFire"), Some(0));
+
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let mut b = Beasts::read(&s).unwrap();
+        assert_eq!(b.get(Beast::Naboris).puzzle.len(), 19);
+        b.set_phase("Naboris", "entered").unwrap();
+        assert_eq!(b.get(Beast::Naboris).terminals_left, TERMINALS);
+        b.set_phase("Rudania", "not_entered").unwrap();
+        b.set_phase("Naboris", "not_entered").unwrap();
+        b.write(&mut s).unwrap();
+        assert_eq!(s.get_bool("RemainsFire_OpenWindow"), Some(false));
+        assert_eq!(s.get_bool("RemainsElectric_FirstRotateNeck"), Some(false));
+        assert_eq!(s.get_bool("RemainsElectric_1st"), Some(true));
+        assert_eq!(s.get_bool("RemainsWind_Stake_1"), Some(true));
+    }
+}
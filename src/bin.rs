@@ -1,13 +1,16 @@
+use botw_editor::folder::Caption;
+use botw_editor::hash::KEYS;
+use botw_editor::metadata::{Metadata, FILENAME as METADATA};
+use botw_editor::teleport::Destination;
+use botw_editor::tracker::Tracker;
+use botw_editor::weather;
 use botw_editor::SaveData;
 use clap::Parser;
 use serde_json::{json, Value};
-use std::fmt;
+use std::path::Path;
 
 use wildmatch::WildMatch;
 
-pub mod hash;
-use hash::KEYS;
-
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -34,84 +37,75 @@ struct Args {
     /// show all values (name, value, hash(name))
     #[arg(short, long)]
     all: bool,
-}
 
-#[derive(Copy, Clone, Debug)]
-enum Weather {
-    Sun = 0,
-    Cloudy = 1,
-    Rain = 2,
-    HeavyRain = 3,
-    Snow = 4,
-    HeavySnow = 5,
-    ThunderStorm = 6,
-    ThunderRain = 7,
-    BlueSkyRain = 8,
-}
-impl From<u64> for Weather {
-    fn from(value: u64) -> Weather {
-        match value {
-            0 => Weather::Sun,
-            1 => Weather::Cloudy,
-            2 => Weather::Rain,
-            3 => Weather::HeavyRain,
-            4 => Weather::Snow,
-            5 => Weather::HeavySnow,
-            6 => Weather::ThunderStorm,
-            7 => Weather::ThunderRain,
-            8 => Weather::BlueSkyRain,
-            _ => panic!("unknown weather value"),
-        }
-    }
-}
+    /// export the Hero's Path to a .geojson, .gpx or .csv file
+    #[arg(short, long)]
+    path: Option<String>,
 
-impl fmt::Display for Weather {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            Weather::Sun => "sun",
-            Weather::Cloudy => "cloudy",
-            Weather::Rain => "rain",
-            Weather::HeavyRain => "heavy_rain",
-            Weather::Snow => "snow",
-            Weather::HeavySnow => "heavy_snow",
-            Weather::ThunderStorm => "thunder_storm",
-            Weather::ThunderRain => "thunder_rain",
-            Weather::BlueSkyRain => "blue_sky_rain",
-        };
-        write!(f, "{}", s)
-    }
+    /// render positions, map pins and the Hero's Path to an .svg file
+    #[arg(short, long)]
+    map: Option<String>,
+
+    /// teleport to x,y,z or a named destination, e.g. TimesShrine, also
+    /// updating the caption.sav next to the save
+    #[arg(short, long)]
+    teleport: Option<String>,
 }
 
 fn converter(name: &str, value: Value) -> Value {
     if name.starts_with("climateWeather") {
-        let mut out = vec![];
-        println!("{value}");
-        for r in value.as_array().unwrap() {
-            let mut tmp = vec![];
-            let k = r.as_u64().unwrap();
-            for i in 0..6 {
-                let w: Weather = (k >> (i * 4) & 0xF).into();
-                let s = format!("{w}");
-                tmp.push(s);
-            }
-            out.push(tmp);
-        }
+        let out: Vec<Vec<_>> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| {
+                weather::decode(r.as_i64().unwrap() as i32)
+                    .iter()
+                    .map(|w| w.map(|w| w.name()).unwrap_or("unknown"))
+                    .collect()
+            })
+            .collect();
         return json!(out);
     }
     value
 }
 
+// Files like the tracker directory sit next to the slot folders
+fn folder_file(input: &str, name: &str) -> String {
+    Path::new(input)
+        .parent()
+        .and_then(|p| p.parent())
+        .unwrap_or(Path::new("."))
+        .join(name)
+        .to_string_lossy()
+        .to_string()
+}
+
+// caption.sav in the same slot folder as a game_data.sav
+fn caption_file(save: &str) -> String {
+    Path::new(save)
+        .with_file_name("caption.sav")
+        .to_string_lossy()
+        .to_string()
+}
+
 fn main() {
     let args = Args::parse();
 
     let mut s = SaveData::read(&args.input).unwrap();
 
+    if let Ok(m) = Metadata::read(&folder_file(&args.input, METADATA)) {
+        if let Some(w) = m.version_warning(s.game_version()) {
+            println!("{w}");
+        }
+    }
+
     for val in args.value {
         //let re = Regex::new(&val).unwrap();
         let re = WildMatch::new(&val);
         let values: Vec<_> = KEYS.iter().filter(|key| re.matches(key)).collect();
         for value in values {
-            match s.get(&value) {
+            match s.get(value) {
                 Ok(v) => println!("{value} {}", converter(value, v)),
                 Err(err) => println!("{}", err),
             }
@@ -120,7 +114,7 @@ fn main() {
 
     if args.all {
         for name in KEYS.iter() {
-            match s.get(&name) {
+            match s.get(name) {
                 Ok(value) => println!(
                     "{:60} {} {}",
                     name,
@@ -132,6 +126,25 @@ fn main() {
         }
     }
 
+    if let Some(filename) = args.path {
+        let t = Tracker::read(&folder_file(&args.input, "tracker")).unwrap();
+        let out = if filename.ends_with(".gpx") {
+            t.gpx()
+        } else if filename.ends_with(".csv") {
+            t.csv()
+        } else {
+            t.geojson().to_string()
+        };
+        println!("Writing Hero's Path to {}...", filename);
+        std::fs::write(&filename, out).unwrap();
+    }
+
+    if let Some(filename) = args.map {
+        let t = Tracker::read(&folder_file(&args.input, "tracker")).unwrap();
+        println!("Writing map to {}...", filename);
+        std::fs::write(&filename, botw_editor::map::svg(&s, Some(&t))).unwrap();
+    }
+
     if !args.set.is_empty() {
         println!("Setting values ...")
     }
@@ -150,11 +163,38 @@ fn main() {
             Err(_err) => {}
         }
     }
-    if args.writeover {
-        println!("Writing output to {}...", args.input);
-        s.write(&args.input).unwrap();
-    } else if let Some(filename) = args.output {
+    let mut caption = None;
+    if let Some(dest) = args.teleport {
+        let teleport = |s: &mut SaveData| -> Result<Option<Caption>, String> {
+            let d = Destination::parse(&dest)?;
+            s.teleport(&d)?;
+            let Ok(sav) = std::fs::read(caption_file(&args.input)) else {
+                return Ok(None);
+            };
+            let mut c = Caption::new(&sav, &[])?;
+            c.teleport(&d)?;
+            Ok(Some(c))
+        };
+        match teleport(&mut s) {
+            Ok(c) => {
+                println!("Teleported to {}", dest);
+                caption = c;
+            }
+            Err(err) => println!("{}", err),
+        }
+    }
+    let output = if args.writeover {
+        Some(args.input)
+    } else {
+        args.output
+    };
+    if let Some(filename) = output {
         println!("Writing output to {}...", filename);
         s.write(&filename).unwrap();
+        if let Some(c) = caption {
+            let name = caption_file(&filename);
+            println!("Writing caption to {}...", name);
+            std::fs::write(&name, c.sav()).unwrap();
+        }
     }
 }
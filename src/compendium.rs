@@ -0,0 +1,238 @@
+//! Hyrule Compendium, `pict_book/<Actor>.jpg` joined with the slot's flags
+//!
+//! Each entry has `IsRegisteredPictureBook_<Actor>`,
+//! `IsNewPictureBook_<Actor>` and `PictureBookSize_<Actor>`.  The size is the
+//! length of the image without padding, or -1 when the entry has no photo.
+//! The images are shared by all slots of a save folder.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::folder::SaveFolder;
+use crate::hash::KEYS;
+use crate::SaveData;
+
+const REGISTERED: &str = "IsRegisteredPictureBook_";
+const NEW: &str = "IsNewPictureBook_";
+const SIZE: &str = "PictureBookSize_";
+const FILE_SIZE: usize = 0x5000;
+const NO_PHOTO: i32 = -1;
+
+fn file_name(actor: &str) -> String {
+    format!("{}.jpg", actor)
+}
+
+/// Actors with a compendium entry, sorted by name
+pub fn actors() -> Vec<&'static str> {
+    let mut v: Vec<_> = KEYS
+        .iter()
+        .filter_map(|k| k.strip_prefix(REGISTERED))
+        .collect();
+    v.sort_unstable();
+    v.dedup();
+    v
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub actor: &'static str,
+    pub registered: bool,
+    pub new: bool,
+    pub size: i32,
+}
+
+impl Entry {
+    pub fn has_photo(&self) -> bool {
+        self.size != NO_PHOTO
+    }
+}
+
+// Actors with a photo in `save`
+fn photos(save: &SaveData) -> impl Iterator<Item = &'static str> + '_ {
+    actors()
+        .into_iter()
+        .filter(|a| save.get_s32(&format!("{}{}", SIZE, a)).unwrap_or(NO_PHOTO) != NO_PHOTO)
+}
+
+#[derive(Clone, Debug)]
+pub struct Compendium {
+    slot: usize,
+    pub entries: Vec<Entry>,
+    images: BTreeMap<String, Vec<u8>>,
+    /// Photos used by the other slots
+    shared: BTreeSet<&'static str>,
+}
+
+impl Compendium {
+    pub fn read(folder: &SaveFolder, slot: usize) -> Result<Compendium, String> {
+        let save = &folder
+            .slot(slot)
+            .ok_or(format!("Error: slot {} is empty", slot))?
+            .save;
+        let mut entries = vec![];
+        for actor in actors() {
+            let (Some(registered), Some(new), Some(size)) = (
+                save.get_bool(&format!("{}{}", REGISTERED, actor)),
+                save.get_bool(&format!("{}{}", NEW, actor)),
+                save.get_s32(&format!("{}{}", SIZE, actor)),
+            ) else {
+                continue;
+            };
+            entries.push(Entry {
+                actor,
+                registered,
+                new,
+                size,
+            });
+        }
+        let shared = folder
+            .slots
+            .iter()
+            .filter(|s| s.index != slot)
+            .flat_map(|s| photos(&s.save))
+            .collect();
+        Ok(Compendium {
+            slot,
+            entries,
+            images: folder.pict_book.clone(),
+            shared,
+        })
+    }
+
+    pub fn entry(&self, actor: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.actor == actor)
+    }
+    fn entry_mut(&mut self, actor: &str) -> Result<&mut Entry, String> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.actor == actor)
+            .ok_or(format!("Error: no compendium entry {}", actor))
+    }
+
+    pub fn registered(&self) -> usize {
+        self.entries.iter().filter(|e| e.registered).count()
+    }
+
+    /// Image without the padding
+    pub fn image(&self, actor: &str) -> Option<&[u8]> {
+        let e = self.entry(actor)?;
+        let jpg = self.images.get(&file_name(actor))?;
+        Some(&jpg[..(e.size.max(0) as usize).min(jpg.len())])
+    }
+
+    /// Entries with a photo size but no image, or a short one
+    pub fn missing(&self) -> Vec<&'static str> {
+        self.entries
+            .iter()
+            .filter(|e| e.has_photo())
+            .filter(|e| match self.images.get(&file_name(e.actor)) {
+                Some(jpg) => jpg.len() < e.size as usize,
+                None => true,
+            })
+            .map(|e| e.actor)
+            .collect()
+    }
+
+    /// Images no slot has a photo size for
+    pub fn orphans(&self) -> Vec<String> {
+        self.images
+            .keys()
+            .filter(|name| {
+                !self
+                    .entries
+                    .iter()
+                    .filter(|e| e.has_photo())
+                    .map(|e| e.actor)
+                    .chain(self.shared.iter().copied())
+                    .any(|a| file_name(a) == **name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Register `actor` as a new entry, with `jpg` as its photo
+    pub fn register(&mut self, actor: &str, jpg: Option<&[u8]>) -> Result<(), String> {
+        if let Some(jpg) = jpg {
+            if !jpg.starts_with(&[0xff, 0xd8]) {
+                return Err(format!("Error: image for {} is not a jpg", actor));
+            }
+            if jpg.len() > FILE_SIZE {
+                return Err(format!(
+                    "Error: image for {} is larger than {} bytes",
+                    actor, FILE_SIZE
+                ));
+            }
+        }
+        let e = self.entry_mut(actor)?;
+        e.registered = true;
+        e.new = true;
+        if let Some(jpg) = jpg {
+            e.size = jpg.len() as i32;
+            let mut jpg = jpg.to_vec();
+            jpg.resize(FILE_SIZE, 0);
+            self.images.insert(file_name(actor), jpg);
+        }
+        Ok(())
+    }
+
+    /// Remove `actor` and its photo, the image is kept if another slot
+    /// uses it
+    pub fn unregister(&mut self, actor: &str) -> Result<(), String> {
+        let e = self.entry_mut(actor)?;
+        e.registered = false;
+        e.new = false;
+        e.size = NO_PHOTO;
+        let actor = e.actor;
+        if !self.shared.contains(actor) {
+            self.images.remove(&file_name(actor));
+        }
+        Ok(())
+    }
+
+    pub fn write(&self, folder: &mut SaveFolder) -> Result<(), String> {
+        let save = &mut folder
+            .slot_mut(self.slot)
+            .ok_or(format!("Error: slot {} is empty", self.slot))?
+            .save;
+        for e in &self.entries {
+            save.set_bool(&format!("{}{}", REGISTERED, e.actor), e.registered)?;
+            save.set_bool(&format!("{}{}", NEW, e.actor), e.new)?;
+            save.set_s32(&format!("{}{}", SIZE, e.actor), e.size)?;
+        }
+        folder.pict_book = self.images.clone();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_and_import() {
+        let mut f = SaveFolder::open("t/+99 cleanup").unwrap();
+        let mut c = Compendium::read(&f, 0).unwrap();
+        assert!(c.entries.len() > 390);
+        assert!(c.missing().is_empty() && c.orphans().is_empty());
+        assert_eq!(c.image("Enemy_Guardian_A").unwrap().len(), 8256);
+        assert!(c.entry("Weapon_Spear_028").unwrap().registered);
+
+        let jpg = c.image("Enemy_Guardian_C").unwrap().to_vec();
+        assert!(c.register("Animal_Bear_A", Some(&[0, 1])).is_err());
+        c.register("Enemy_Lynel_Senior", Some(&jpg)).unwrap();
+        c.unregister("Item_Mushroom_C").unwrap();
+        c.write(&mut f).unwrap();
+
+        let s = &f.slot(0).unwrap().save;
+        assert_eq!(s.get_s32("PictureBookSize_Enemy_Lynel_Senior"), Some(10444));
+        assert_eq!(
+            s.get_bool("IsNewPictureBook_Enemy_Lynel_Senior"),
+            Some(true)
+        );
+        // Slot 1 still has a photo of the mushroom
+        assert!(f.pict_book.contains_key("Item_Mushroom_C.jpg"));
+        f.pict_book.insert("Enemy_Lynel_Gold.jpg".to_string(), jpg);
+        let c = Compendium::read(&f, 1).unwrap();
+        assert!(c.missing().is_empty());
+        assert_eq!(c.orphans(), vec!["Enemy_Lynel_Gold.jpg"]);
+    }
+}
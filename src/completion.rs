@@ -0,0 +1,122 @@
+//! Completion, counted from the save's discovery flags
+//!
+//! Locations are the `Location_*` names shown on arrival, counted once
+//! shown; the shrines' `Location_DungeonNNN` are left to the shrines.
+//! That leaves 200, and they are not all map places: the 15 towers, the
+//! 4 Divine Beasts (`Location_Remains*`), 15 halls and rooms of Hyrule
+//! Castle, 4 Tarrey Town shops and 7 minigames are among them, next to
+//! the stables, bridges and villages. A save near 100% ("t/+99 cleanup")
+//! still misses 17 of them, `Location_DungeonMush` and
+//! `Location_HyruleCastle_Room_4` among those, so the list is wider than
+//! what the game counts; which of them it counts is not known yet.
+//! Shrines are the base game's 120, a shrine counts once cleared.
+//! The game's own percentage weighs these differently and the weights are
+//! not in the save, so no overall percentage is given; the parts are
+//! counted on their own.
+
+use crate::hash::KEYS;
+use crate::korok::Koroks;
+use crate::shrine::Shrines;
+use crate::SaveData;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Part {
+    pub found: usize,
+    pub total: usize,
+    /// Names of what is not found yet
+    pub missing: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+    pub locations: Part,
+    pub koroks: Part,
+    pub shrines: Part,
+}
+
+impl Completion {
+    pub fn found(&self) -> usize {
+        self.locations.found + self.koroks.found + self.shrines.found
+    }
+    pub fn total(&self) -> usize {
+        self.locations.total + self.koroks.total + self.shrines.total
+    }
+}
+
+/// `DungeonNNN`, including `Dungeon136` which is no shrine
+fn is_shrine(name: &str) -> bool {
+    name.strip_prefix("Dungeon")
+        .is_some_and(|n| n.len() == 3 && n.parse::<u32>().is_ok())
+}
+
+fn part(items: impl Iterator<Item = (String, bool)>) -> Part {
+    let mut p = Part::default();
+    for (name, found) in items {
+        p.total += 1;
+        if found {
+            p.found += 1;
+        } else {
+            p.missing.push(name);
+        }
+    }
+    p
+}
+
+impl SaveData {
+    pub fn completion(&self) -> Result<Completion, String> {
+        let mut locations: Vec<_> = KEYS
+            .iter()
+            .filter_map(|k| k.strip_prefix("Location_"))
+            .filter(|name| !is_shrine(name))
+            .filter_map(|name| {
+                Some((
+                    name.to_string(),
+                    self.get_s32(&format!("Location_{}", name))? > 0,
+                ))
+            })
+            .collect();
+        locations.sort();
+        let koroks = Koroks::read(self)?;
+        let shrines = Shrines::read(self)?;
+        Ok(Completion {
+            locations: part(locations.into_iter()),
+            koroks: part(koroks.seeds.iter().map(|s| (s.flag(), s.found))),
+            shrines: part(
+                shrines
+                    .shrines
+                    .iter()
+                    .filter(|s| !s.is_dlc())
+                    .map(|s| (s.name(), s.cleared)),
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breakdown() {
+        let s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let c = s.completion().unwrap();
+        assert_eq!((c.koroks.found, c.koroks.total), (877, 900));
+        assert_eq!((c.shrines.found, c.shrines.total), (116, 120));
+        assert_eq!(
+            c.shrines.missing,
+            ["Dungeon018", "Dungeon042", "Dungeon062", "Dungeon115"]
+        );
+        assert_eq!((c.locations.found, c.locations.total), (179, 200));
+        assert!(c.locations.missing.contains(&"LakeHylia".to_string()));
+        assert!(!c
+            .locations
+            .missing
+            .iter()
+            .any(|l| l.starts_with("Dungeon1")));
+        assert_eq!(c.found(), c.locations.found + 993);
+
+        let new = SaveData::read("t/+99 IST/0/game_data.sav").unwrap();
+        let c = new.completion().unwrap();
+        assert!(c.found() < c.total() / 100);
+    }
+}
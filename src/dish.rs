@@ -0,0 +1,217 @@
+//! Cooked dishes, a typed view of the pouch's cooking arrays
+//!
+//! `CookEffect0` holds the effect id and level, `StaminaRecover` the hearts
+//! restored (in quarter hearts) and the effect time in seconds, and
+//! `CookMaterialName0..4` the ingredients.  `CookEffect1` holds the sell
+//! price, which is left as it is.
+
+use crate::inventory::{CookData, Inventory, InventoryItem, MATERIALS};
+
+/// Actor shared by all elixirs, the effect picks the colour and name
+pub const ELIXIR: &str = "Item_Cook_C_17";
+/// Longest effect time the game gives a dish, 30:00
+pub const MAX_DURATION: u32 = 30 * 60;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    None,
+    Hearty,
+    Chilly,
+    Spicy,
+    Electro,
+    Mighty,
+    Tough,
+    Sneaky,
+    Hasty,
+    Energizing,
+    Enduring,
+    Fireproof,
+}
+
+const EFFECTS: [(Effect, i32, &str); 12] = [
+    (Effect::None, -1, "none"),
+    (Effect::Hearty, 2, "hearty"),
+    (Effect::Chilly, 4, "chilly"),
+    (Effect::Spicy, 5, "spicy"),
+    (Effect::Electro, 6, "electro"),
+    (Effect::Mighty, 10, "mighty"),
+    (Effect::Tough, 11, "tough"),
+    (Effect::Sneaky, 12, "sneaky"),
+    (Effect::Hasty, 13, "hasty"),
+    (Effect::Energizing, 14, "energizing"),
+    (Effect::Enduring, 15, "enduring"),
+    (Effect::Fireproof, 16, "fireproof"),
+];
+
+impl Effect {
+    pub fn id(&self) -> i32 {
+        EFFECTS.iter().find(|e| e.0 == *self).unwrap().1
+    }
+    pub fn from_id(id: i32) -> Option<Effect> {
+        EFFECTS.iter().find(|e| e.1 == id).map(|e| e.0)
+    }
+    pub fn name(&self) -> &'static str {
+        EFFECTS.iter().find(|e| e.0 == *self).unwrap().2
+    }
+    pub fn from_name(name: &str) -> Option<Effect> {
+        EFFECTS
+            .iter()
+            .find(|e| e.2.eq_ignore_ascii_case(name))
+            .map(|e| e.0)
+    }
+
+    /// Effects that last for a time and come in levels 1 to 3
+    pub fn is_timed(&self) -> bool {
+        !matches!(
+            self,
+            Effect::None | Effect::Hearty | Effect::Energizing | Effect::Enduring
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dish {
+    pub effect: Effect,
+    pub level: f32,
+    /// Effect time in seconds
+    pub duration: u32,
+    pub hearts: f32,
+    pub ingredients: Vec<String>,
+}
+
+impl Dish {
+    pub fn new(effect: Effect, level: f32, duration: u32) -> Dish {
+        Dish {
+            effect,
+            level,
+            duration,
+            hearts: 0.0,
+            ingredients: vec![],
+        }
+    }
+
+    pub fn from_cook(cook: &CookData) -> Option<Dish> {
+        Some(Dish {
+            effect: Effect::from_id(cook.effect[0] as i32)?,
+            level: cook.effect[1],
+            duration: cook.recover[1].max(0.0) as u32,
+            hearts: cook.recover[0].max(0.0) / 4.0,
+            ingredients: cook
+                .materials
+                .iter()
+                .filter(|m| !m.is_empty())
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Cooking data for the dish, keeping the price of `old`
+    pub fn to_cook(&self, old: Option<&CookData>) -> CookData {
+        let old = old.cloned().unwrap_or_default();
+        CookData {
+            effect: [self.effect.id() as f32, self.level],
+            effect1: old.effect1,
+            recover: [self.hearts * 4.0, self.duration as f32],
+            materials: std::array::from_fn(|i| {
+                self.ingredients.get(i).cloned().unwrap_or_default()
+            }),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ingredients.len() > MATERIALS {
+            return Err(format!(
+                "Error: dishes have at most {} ingredients",
+                MATERIALS
+            ));
+        }
+        if self.hearts < 0.0 || (self.hearts * 4.0).fract() != 0.0 {
+            return Err(format!(
+                "Error: {} is not a number of quarter hearts",
+                self.hearts
+            ));
+        }
+        if self.duration > MAX_DURATION {
+            return Err(format!(
+                "Error: effect time {} is longer than 30:00",
+                self.duration
+            ));
+        }
+        if self.effect.is_timed() && !(1.0..=3.0).contains(&self.level) {
+            return Err(format!(
+                "Error: {} level {} is outside 1 to 3",
+                self.effect.name(),
+                self.level
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Inventory {
+    /// Food items with their pouch index
+    pub fn dishes(&self) -> Vec<(usize, Dish)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| Some((i, Dish::from_cook(item.cook.as_ref()?)?)))
+            .collect()
+    }
+
+    pub fn set_dish(&mut self, index: usize, dish: &Dish) -> Result<(), String> {
+        dish.validate()?;
+        let item = self
+            .items
+            .get_mut(index)
+            .ok_or(format!("Error: no item {}", index))?;
+        if item.cook.is_none() {
+            return Err(format!("Error: {} is not food", item.name));
+        }
+        item.cook = Some(dish.to_cook(item.cook.as_ref()));
+        Ok(())
+    }
+
+    /// Add a dish cooked as `actor`, e.g. `ELIXIR`, returns its index
+    pub fn add_dish(&mut self, actor: &str, dish: &Dish) -> Result<usize, String> {
+        dish.validate()?;
+        let mut item = InventoryItem::new(actor, 1);
+        if item.cook.is_none() {
+            return Err(format!("Error: {} is not food", actor));
+        }
+        item.cook = Some(dish.to_cook(None));
+        self.add(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SaveData;
+
+    #[test]
+    fn cook_elixir() {
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let mut inv = Inventory::read(&s).unwrap();
+        let dishes = inv.dishes();
+        let (i, d) = &dishes[5];
+        assert_eq!(*i, 94);
+        assert_eq!((d.effect, d.level, d.duration), (Effect::Hasty, 3.0, 1800));
+        assert_eq!(d.hearts, 7.75);
+        assert_eq!(d.ingredients.len(), 5);
+
+        let mut elixir = Dish::new(Effect::Hasty, 3.0, 180);
+        elixir.ingredients = vec!["Animal_Insect_B".to_string(), "Item_Enemy_00".to_string()];
+        let i = inv.add_dish(ELIXIR, &elixir).unwrap();
+        assert!(inv
+            .add_dish(ELIXIR, &Dish::new(Effect::Sneaky, 4.0, 60))
+            .is_err());
+        assert!(inv.set_dish(0, &elixir).is_err());
+        inv.write(&mut s).unwrap();
+
+        let inv = Inventory::read(&s).unwrap();
+        assert_eq!(inv.items[i].name, ELIXIR);
+        let d = Dish::from_cook(inv.items[i].cook.as_ref().unwrap()).unwrap();
+        assert_eq!(d, elixir);
+        assert_eq!(inv.dishes().len(), dishes.len() + 1);
+    }
+}
@@ -0,0 +1,285 @@
+//! Enemies defeated, the `Defeated_<Actor>_Num` counters
+//!
+//! There is one s32 counter per enemy with a Compendium entry.  They stop
+//! at 10: in the saves here eight sit at 10 and none is higher, while the
+//! uncapped totals below run past the sum of their enemies' counters, e.g.
+//! 40 Taluses against 37.  Larger counts are refused.  A defeated enemy
+//! should be registered in the Compendium, one that is not is reported.
+//!
+//! `DefeatedGolemNum`, `DefeatedSandwormNum` and `DefeatedForestGiantNum`
+//! are the game's totals of Taluses, Moldugas and Hinoxes defeated.
+
+use crate::compendium;
+use crate::hash::KEYS;
+use crate::SaveData;
+
+const PREFIX: &str = "Defeated_";
+const SUFFIX: &str = "_Num";
+pub const MAX: i32 = 10;
+const GOLEMS: &str = "DefeatedGolemNum";
+const SANDWORMS: &str = "DefeatedSandwormNum";
+const FOREST_GIANTS: &str = "DefeatedForestGiantNum";
+
+/// Names for actors that do not read well, e.g. `Enemy_Moriblin_Middle`
+const NAMES: [(&str, &str); 71] = [
+    ("Enemy_Bokoblin_Junior", "Bokoblin"),
+    ("Enemy_Bokoblin_Middle", "Blue Bokoblin"),
+    ("Enemy_Bokoblin_Senior", "Black Bokoblin"),
+    ("Enemy_Bokoblin_Dark", "Silver Bokoblin"),
+    ("Enemy_Bokoblin_Gold", "Gold Bokoblin"),
+    ("Enemy_Bokoblin_Bone_Junior", "Stalkoblin"),
+    ("Enemy_Moriblin_Junior", "Moblin"),
+    ("Enemy_Moriblin_Middle", "Blue Moblin"),
+    ("Enemy_Moriblin_Senior", "Black Moblin"),
+    ("Enemy_Moriblin_Dark", "Silver Moblin"),
+    ("Enemy_Moriblin_Gold", "Gold Moblin"),
+    ("Enemy_Moriblin_Bone_Junior", "Stalmoblin"),
+    ("Enemy_Lizalfos_Junior", "Lizalfos"),
+    ("Enemy_Lizalfos_Middle", "Blue Lizalfos"),
+    ("Enemy_Lizalfos_Senior", "Black Lizalfos"),
+    ("Enemy_Lizalfos_Dark", "Silver Lizalfos"),
+    ("Enemy_Lizalfos_Gold", "Gold Lizalfos"),
+    ("Enemy_Lizalfos_Fire", "Fire-Breath Lizalfos"),
+    ("Enemy_Lizalfos_Ice", "Ice-Breath Lizalfos"),
+    ("Enemy_Lizalfos_Electric", "Electric Lizalfos"),
+    ("Enemy_Lizalfos_Bone_Junior", "Stalizalfos"),
+    ("Enemy_Lynel_Junior", "Lynel"),
+    ("Enemy_Lynel_Middle", "Blue-Maned Lynel"),
+    ("Enemy_Lynel_Senior", "White-Maned Lynel"),
+    ("Enemy_Lynel_Dark", "Silver Lynel"),
+    ("Enemy_Lynel_Gold", "Gold Lynel"),
+    ("Enemy_Giant_Junior", "Hinox"),
+    ("Enemy_Giant_Middle", "Blue Hinox"),
+    ("Enemy_Giant_Senior", "Black Hinox"),
+    ("Enemy_Giant_Bone", "Stalnox"),
+    ("Enemy_Golem_Junior", "Stone Talus"),
+    ("Enemy_Golem_Middle", "Stone Talus (Luminous)"),
+    ("Enemy_Golem_Senior", "Stone Talus (Rare)"),
+    ("Enemy_Golem_Ice", "Frost Talus"),
+    ("Enemy_Golem_Fire_R", "Igneo Talus Titan"),
+    ("Enemy_Golem_Little", "Stone Pebblit"),
+    ("Enemy_Golem_Little_Fire", "Igneo Pebblit"),
+    ("Enemy_Golem_Little_Ice", "Frost Pebblit"),
+    ("Enemy_Chuchu_Junior", "Chuchu"),
+    ("Enemy_Chuchu_Fire_Junior", "Fire Chuchu"),
+    ("Enemy_Chuchu_Ice_Junior", "Ice Chuchu"),
+    ("Enemy_Keese_Fire", "Fire Keese"),
+    ("Enemy_Keese_Ice", "Ice Keese"),
+    ("Enemy_Keese_Electric", "Electric Keese"),
+    ("Enemy_Octarock", "Water Octorok"),
+    ("Enemy_Octarock_Stone", "Rock Octorok"),
+    ("Enemy_Octarock_Snow", "Snow Octorok"),
+    ("Enemy_Octarock_Forest", "Treetop Octorok"),
+    ("Enemy_Octarock_Desert", "Sand Octorok"),
+    ("Enemy_AirOctarock", "Sky Octorok"),
+    ("Enemy_Wizzrobe_Fire", "Fire Wizzrobe"),
+    ("Enemy_Wizzrobe_Fire_Senior", "Meteo Wizzrobe"),
+    ("Enemy_Wizzrobe_Ice", "Ice Wizzrobe"),
+    ("Enemy_Wizzrobe_Ice_Senior", "Blizzrobe"),
+    ("Enemy_Wizzrobe_Electric", "Electric Wizzrobe"),
+    ("Enemy_Wizzrobe_Electric_Senior", "Thunder Wizzrobe"),
+    ("Enemy_Sandworm", "Molduga"),
+    ("Enemy_SandwormR", "Molduking"),
+    ("Enemy_Guardian_A", "Guardian Stalker"),
+    ("Enemy_Guardian_B", "Guardian Skywatcher"),
+    ("Enemy_Guardian_C", "Guardian Turret"),
+    ("Enemy_Guardian_A_Fixed_Moss", "Decayed Guardian"),
+    ("Enemy_Guardian_Mini_Baby", "Guardian Scout I"),
+    ("Enemy_Guardian_Mini_Junior", "Guardian Scout II"),
+    ("Enemy_Guardian_Mini_Middle", "Guardian Scout III"),
+    ("Enemy_Guardian_Mini_Senior", "Guardian Scout IV"),
+    ("Enemy_SiteBoss_Lsword", "Fireblight Ganon"),
+    ("Enemy_SiteBoss_Spear", "Waterblight Ganon"),
+    ("Enemy_SiteBoss_Bow", "Windblight Ganon"),
+    ("Enemy_SiteBoss_Sword", "Thunderblight Ganon"),
+    ("Enemy_GanonBeast", "Dark Beast Ganon"),
+];
+
+/// Readable name of an actor, e.g. `Blue Moblin` or `Keese`
+pub fn name(actor: &str) -> String {
+    match NAMES.iter().find(|n| n.0 == actor) {
+        Some(n) => n.1.to_string(),
+        None => actor
+            .strip_prefix("Enemy_")
+            .unwrap_or(actor)
+            .replace('_', " "),
+    }
+}
+
+/// Actors with a counter, sorted
+pub fn actors() -> Vec<&'static str> {
+    let mut v: Vec<_> = KEYS
+        .iter()
+        .filter_map(|k| k.strip_prefix(PREFIX)?.strip_suffix(SUFFIX))
+        .collect();
+    v.sort_unstable();
+    v
+}
+
+fn flag(actor: &str) -> String {
+    format!("{}{}{}", PREFIX, actor, SUFFIX)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Count {
+    pub actor: &'static str,
+    pub defeated: i32,
+    /// Registered in the Compendium
+    pub registered: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnemyStats {
+    pub counts: Vec<Count>,
+    /// Taluses defeated, not capped
+    pub golems: i32,
+    /// Moldugas defeated, not capped
+    pub sandworms: i32,
+    /// Hinoxes defeated, not capped
+    pub forest_giants: i32,
+}
+
+impl EnemyStats {
+    pub fn read(save: &SaveData) -> Result<EnemyStats, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        let mut counts = vec![];
+        for actor in actors() {
+            let key = flag(actor);
+            counts.push(Count {
+                actor,
+                defeated: save.get_s32(&key).ok_or(missing(&key))?,
+                registered: save
+                    .get_bool(&format!("IsRegisteredPictureBook_{}", actor))
+                    .unwrap_or(false),
+            });
+        }
+        Ok(EnemyStats {
+            counts,
+            golems: save.get_s32(GOLEMS).ok_or(missing(GOLEMS))?,
+            sandworms: save.get_s32(SANDWORMS).ok_or(missing(SANDWORMS))?,
+            forest_giants: save.get_s32(FOREST_GIANTS).ok_or(missing(FOREST_GIANTS))?,
+        })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for c in &self.counts {
+            save.set_s32(&flag(c.actor), c.defeated)?;
+        }
+        save.set_s32(GOLEMS, self.golems)?;
+        save.set_s32(SANDWORMS, self.sandworms)?;
+        save.set_s32(FOREST_GIANTS, self.forest_giants)
+    }
+
+    /// Count by actor or readable name
+    pub fn get(&self, enemy: &str) -> Option<&Count> {
+        self.counts
+            .iter()
+            .find(|c| c.actor == enemy || name(c.actor).eq_ignore_ascii_case(enemy))
+    }
+
+    pub fn set(&mut self, enemy: &str, defeated: i32) -> Result<(), String> {
+        if !(0..=MAX).contains(&defeated) {
+            return Err(format!(
+                "Error: defeated count {} is outside 0 to {}",
+                defeated, MAX
+            ));
+        }
+        let actor = self
+            .get(enemy)
+            .ok_or(format!("Error: no enemy {}", enemy))?
+            .actor;
+        self.counts
+            .iter_mut()
+            .find(|c| c.actor == actor)
+            .unwrap()
+            .defeated = defeated;
+        Ok(())
+    }
+
+    pub fn total(&self) -> i32 {
+        self.counts.iter().map(|c| c.defeated).sum()
+    }
+
+    /// Enemies defeated at least once
+    pub fn defeated(&self) -> Vec<&Count> {
+        self.counts.iter().filter(|c| c.defeated > 0).collect()
+    }
+
+    /// Problems with the counts: out of range, for an actor without a
+    /// Compendium entry, or defeated but not registered in the Compendium
+    pub fn validate(&self) -> Vec<String> {
+        let entries = compendium::actors();
+        let mut out = vec![];
+        for c in &self.counts {
+            if !(0..=MAX).contains(&c.defeated) {
+                out.push(format!(
+                    "{} defeated {} times, outside 0 to {}",
+                    c.actor, c.defeated, MAX
+                ));
+            }
+            if entries.binary_search(&c.actor).is_err() {
+                out.push(format!("{} has no Compendium entry", c.actor));
+            } else if c.defeated > 0 && !c.registered {
+                out.push(format!(
+                    "{} defeated {} times, not in the Compendium",
+                    c.actor, c.defeated
+                ));
+            }
+        }
+        for (key, n) in [
+            (GOLEMS, self.golems),
+            (SANDWORMS, self.sandworms),
+            (FOREST_GIANTS, self.forest_giants),
+        ] {
+            if n < 0 {
+                out.push(format!("{} is {}", key, n));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_and_edit() {
+        let mut s = SaveData::read("t/3AA Blights Segment Start v2/0/game_data.sav").unwrap();
+        let mut e = EnemyStats::read(&s).unwrap();
+        assert_eq!(e.counts.len(), 82);
+        let bokoblin = e.get("Bokoblin").unwrap();
+        assert_eq!(bokoblin.actor, "Enemy_Bokoblin_Junior");
+        assert_eq!(bokoblin.defeated, 1);
+        assert!(!bokoblin.registered);
+        assert_eq!(name("Enemy_Keese"), "Keese");
+        // Defeated on the way, never photographed
+        let problems = e.validate();
+        assert_eq!(problems.len(), 3);
+        assert_eq!(
+            problems[0],
+            "Enemy_Bokoblin_Junior defeated 1 times, not in the Compendium"
+        );
+
+        assert!(e.set("Blue Moblin", MAX + 1).is_err());
+        assert!(e.set("Ganondorf", 1).is_err());
+        e.set("blue moblin", 4).unwrap();
+        e.set("Enemy_Lynel_Gold", MAX).unwrap();
+        e.write(&mut s).unwrap();
+        let mut e = EnemyStats::read(&s).unwrap();
+        assert_eq!(s.get_s32("Defeated_Enemy_Moriblin_Middle_Num"), Some(4));
+        assert_eq!(e.total(), 4 + 4 + MAX);
+        assert_eq!(e.defeated().len(), 5);
+
+        assert_eq!(e.validate().len(), 5);
+        e.golems = -1;
+        assert_eq!(e.validate().len(), 6);
+        let done =
+            EnemyStats::read(&SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap()).unwrap();
+        assert_eq!(
+            (done.golems, done.sandworms, done.forest_giants),
+            (40, 4, 39)
+        );
+        assert_eq!(done.validate(), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,491 @@
+//! A complete save directory as written by the game
+//!
+//! ```text
+//! 0/ .. 7/                  game_data.sav, caption.sav, caption.jpg
+//! option.sav
+//! album/pict_NNN.jpg
+//! pict_book/<Actor>.jpg
+//! tracker/trackblockNN.sav, trackblock_hardNN.sav
+//! edizon_save_metadata.json
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::get_hash;
+use crate::metadata::{Layout, Metadata, FILENAME as METADATA};
+use crate::tracker::Tracker;
+use crate::SaveData;
+
+pub const SLOTS: usize = 8;
+/// Slots 0..=4 rotate as autosaves, 5 is the manual save
+const MANUAL_SLOT: usize = 5;
+/// Slots 6 and 7 belong to Master Mode
+const MASTER_SLOT: usize = 6;
+
+/// `caption.sav` and the `caption.jpg` screenshot it describes
+#[derive(Clone, Debug)]
+pub struct Caption {
+    save: SaveData,
+    pub jpg: Vec<u8>,
+}
+
+impl Caption {
+    pub fn new(sav: &[u8], jpg: &[u8]) -> Result<Caption, String> {
+        let save = SaveData::new(sav).map_err(|e| format!("Error: bad caption.sav: {}", e))?;
+        Ok(Caption {
+            save,
+            jpg: jpg.to_vec(),
+        })
+    }
+    /// Location message name, e.g. `HyruleCastle` or `Dungeon039`
+    pub fn location(&self) -> String {
+        self.save
+            .raw_str(get_hash("SaveLocationName"), 256)
+            .unwrap_or_default()
+    }
+    /// District message name, e.g. `MapArea_CentralHyrule`
+    pub fn district(&self) -> String {
+        self.save
+            .raw_str(get_hash("SaveDistrictName"), 256)
+            .unwrap_or_default()
+    }
+    /// Set the load screen location, None keeps the district
+    pub fn set_location(&mut self, location: &str, district: Option<&str>) -> Result<(), String> {
+        self.save
+            .set_raw_str(get_hash("SaveLocationName"), location, 256)?;
+        if let Some(district) = district {
+            self.save
+                .set_raw_str(get_hash("SaveDistrictName"), district, 256)?;
+        }
+        Ok(())
+    }
+    /// Real time of the save, seconds since the epoch
+    pub fn time(&self) -> u32 {
+        // Key name unknown
+        self.save.raw_s32(0x0ba7cf40).unwrap_or(0) as u32
+    }
+    pub fn game_clear(&self) -> bool {
+        self.save.raw_s32(get_hash("GameClear")).unwrap_or(0) != 0
+    }
+    pub fn hard_mode(&self) -> bool {
+        self.save
+            .raw_s32(get_hash("AoC_HardMode_Enabled"))
+            .unwrap_or(0)
+            != 0
+    }
+    pub fn sav(&self) -> Vec<u8> {
+        let mut data = vec![0; self.save.size()];
+        self.save.data(&mut data);
+        data
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SlotKind {
+    Autosave,
+    Manual,
+    MasterMode,
+}
+
+impl SlotKind {
+    pub fn of(index: usize) -> SlotKind {
+        match index {
+            i if i >= MASTER_SLOT => SlotKind::MasterMode,
+            MANUAL_SLOT => SlotKind::Manual,
+            _ => SlotKind::Autosave,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Slot {
+    pub index: usize,
+    pub save: SaveData,
+    pub caption: Caption,
+}
+
+#[derive(Clone, Debug)]
+pub struct SaveFolder {
+    pub path: PathBuf,
+    /// Present slots in index order
+    pub slots: Vec<Slot>,
+    pub option: Option<SaveData>,
+    /// `album` files by name
+    pub album: BTreeMap<String, Vec<u8>>,
+    /// `pict_book` files by name
+    pub pict_book: BTreeMap<String, Vec<u8>>,
+    pub tracker: Tracker,
+    pub metadata: Option<Metadata>,
+}
+
+fn err<E: std::fmt::Display>(path: &Path) -> impl Fn(E) -> String + '_ {
+    move |e| format!("Error: {}: {}", path.display(), e)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(err(path))
+}
+
+fn write(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(err(dir))?;
+    }
+    fs::write(path, data).map_err(err(path))
+}
+
+fn read_files(dir: &Path) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let mut out = BTreeMap::new();
+    if !dir.is_dir() {
+        return Ok(out);
+    }
+    for entry in fs::read_dir(dir).map_err(err(dir))? {
+        let path = entry.map_err(err(dir))?.path();
+        if path.is_file() {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            if !name.starts_with('.') {
+                out.insert(name, read(&path)?);
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Files and directories owned by the model, everything else is carried over
+fn is_modeled(name: &str) -> bool {
+    matches!(
+        name,
+        "option.sav" | "album" | "pict_book" | "tracker" | METADATA
+    ) || name.parse::<usize>().is_ok_and(|i| i < SLOTS)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(err(to))?;
+    for entry in fs::read_dir(from).map_err(err(from))? {
+        let path = entry.map_err(err(from))?.path();
+        let dst = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &dst)?;
+        } else {
+            fs::copy(&path, &dst).map_err(err(&path))?;
+        }
+    }
+    Ok(())
+}
+
+impl Slot {
+    pub fn read(dir: &Path, index: usize) -> Result<Slot, String> {
+        let name = dir.join("game_data.sav");
+        let save = SaveData::new(&read(&name)?).map_err(err(&name))?;
+        let caption = Caption::new(
+            &read(&dir.join("caption.sav"))?,
+            &read(&dir.join("caption.jpg"))?,
+        )?;
+        Ok(Slot {
+            index,
+            save,
+            caption,
+        })
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<(), String> {
+        let mut data = vec![0; self.save.size()];
+        self.save.data(&mut data);
+        write(&dir.join("game_data.sav"), &data)?;
+        write(&dir.join("caption.sav"), &self.caption.sav())?;
+        write(&dir.join("caption.jpg"), &self.caption.jpg)
+    }
+}
+
+impl SaveFolder {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SaveFolder, String> {
+        let path = path.as_ref().to_path_buf();
+        if !path.is_dir() {
+            return Err(format!("Error: {} is not a directory", path.display()));
+        }
+        let mut slots = vec![];
+        for i in 0..SLOTS {
+            let dir = path.join(i.to_string());
+            if dir.join("game_data.sav").is_file() {
+                slots.push(Slot::read(&dir, i)?);
+            }
+        }
+        let name = path.join("option.sav");
+        let option = match name.is_file() {
+            true => Some(SaveData::new(&read(&name)?).map_err(err(&name))?),
+            false => None,
+        };
+        let name = path.join(METADATA);
+        let metadata = match name.is_file() {
+            true => Some(Metadata::from_json(&read(&name)?)?),
+            false => None,
+        };
+        let tracker = Tracker::read(&path.join("tracker").to_string_lossy())?;
+        Ok(SaveFolder {
+            album: read_files(&path.join("album"))?,
+            pict_book: read_files(&path.join("pict_book"))?,
+            path,
+            slots,
+            option,
+            tracker,
+            metadata,
+        })
+    }
+
+    pub fn slot(&self, index: usize) -> Option<&Slot> {
+        self.slots.iter().find(|s| s.index == index)
+    }
+    pub fn slot_mut(&mut self, index: usize) -> Option<&mut Slot> {
+        self.slots.iter_mut().find(|s| s.index == index)
+    }
+
+    /// Slot class from its number, Master Mode also from the caption.
+    /// `trackblock_hard` only tells that Master Mode was played, not which
+    /// slots hold it, so it is not used.
+    pub fn kind(&self, index: usize) -> Option<SlotKind> {
+        let slot = self.slot(index)?;
+        if slot.caption.hard_mode() {
+            return Some(SlotKind::MasterMode);
+        }
+        Some(SlotKind::of(index))
+    }
+
+    fn insert(&mut self, slot: Slot) {
+        self.slots.retain(|s| s.index != slot.index);
+        self.slots.push(slot);
+        self.slots.sort_by_key(|s| s.index);
+    }
+
+    fn check(&self, slot: &Slot, to: usize) -> Result<(), String> {
+        if to >= SLOTS {
+            return Err(format!("Error: slot {} out of range 0..{}", to, SLOTS));
+        }
+        let master = SlotKind::of(to) == SlotKind::MasterMode;
+        if slot.caption.hard_mode() != master {
+            return Err(format!(
+                "Error: slot {} {} a Master Mode save, slot {} {}",
+                slot.index,
+                if slot.caption.hard_mode() {
+                    "is"
+                } else {
+                    "is not"
+                },
+                to,
+                if master {
+                    "is for Master Mode"
+                } else {
+                    "is not for Master Mode"
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copy `from` with its caption over `to`
+    pub fn copy_slot(&mut self, from: usize, to: usize) -> Result<(), String> {
+        let mut slot = self
+            .slot(from)
+            .ok_or(format!("Error: slot {} is empty", from))?
+            .clone();
+        self.check(&slot, to)?;
+        slot.index = to;
+        self.insert(slot);
+        Ok(())
+    }
+
+    /// Swap two slots, either one may be empty
+    pub fn swap_slots(&mut self, a: usize, b: usize) -> Result<(), String> {
+        if a.max(b) >= SLOTS {
+            return Err(format!(
+                "Error: slot {} out of range 0..{}",
+                a.max(b),
+                SLOTS
+            ));
+        }
+        for (x, y) in [(a, b), (b, a)] {
+            if let Some(slot) = self.slot(x) {
+                self.check(slot, y)?;
+            }
+        }
+        for slot in self.slots.iter_mut() {
+            if slot.index == a {
+                slot.index = b;
+            } else if slot.index == b {
+                slot.index = a;
+            }
+        }
+        self.slots.sort_by_key(|s| s.index);
+        Ok(())
+    }
+
+    /// Remove a slot, the folder is deleted on save
+    pub fn clear_slot(&mut self, index: usize) -> Option<Slot> {
+        let i = self.slots.iter().position(|s| s.index == index)?;
+        Some(self.slots.remove(i))
+    }
+
+    /// Write the modeled files into `dir`
+    pub fn write(&self, dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(dir).map_err(err(dir))?;
+        for slot in &self.slots {
+            slot.write(&dir.join(slot.index.to_string()))?;
+        }
+        if let Some(option) = &self.option {
+            let mut data = vec![0; option.size()];
+            option.data(&mut data);
+            write(&dir.join("option.sav"), &data)?;
+        }
+        for (name, data) in &self.album {
+            write(&dir.join("album").join(name), data)?;
+        }
+        for (name, data) in &self.pict_book {
+            write(&dir.join("pict_book").join(name), data)?;
+        }
+        if !self.tracker.normal.is_empty() || !self.tracker.master.is_empty() {
+            self.tracker.write(&dir.join("tracker").to_string_lossy())?;
+        }
+        if let Some(metadata) = &self.metadata {
+            write(&dir.join(METADATA), metadata.to_json().as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Write a backup a save manager can restore, returns its directory
+    ///
+    /// `root` is the SD card root and `name` the backup name, usually a
+    /// timestamp.  The metadata is required for the title and user.
+    pub fn export(&self, root: &Path, layout: Layout, name: &str) -> Result<PathBuf, String> {
+        let metadata = self.metadata.as_ref().ok_or(format!(
+            "Error: {} has no {}",
+            self.path.display(),
+            METADATA
+        ))?;
+        let dir = root.join(metadata.backup_dir(layout, name));
+        if dir.exists() {
+            return Err(format!("Error: {} already exists", dir.display()));
+        }
+        self.write(&dir)?;
+        Ok(dir)
+    }
+
+    /// Disagreements between the metadata and the slots
+    pub fn warnings(&self) -> Vec<String> {
+        let mut out = vec![];
+        if let Some(m) = &self.metadata {
+            for slot in &self.slots {
+                if let Some(w) = m.version_warning(slot.save.game_version()) {
+                    out.push(format!("slot {}: {}", slot.index, w));
+                }
+            }
+        }
+        out
+    }
+
+    /// Replace the folder on disk
+    ///
+    /// The new folder is written next to the old one and swapped in with
+    /// renames, so a failure leaves the original untouched.  Files the
+    /// model does not know about are carried over.
+    pub fn save(&self) -> Result<(), String> {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let tmp = self.path.with_file_name(format!(".{}.tmp", name));
+        let bak = self.path.with_file_name(format!(".{}.bak", name));
+        for dir in [&tmp, &bak] {
+            if dir.exists() {
+                fs::remove_dir_all(dir).map_err(err(dir))?;
+            }
+        }
+        let result = self.write(&tmp).and_then(|_| {
+            for entry in fs::read_dir(&self.path).map_err(err(&self.path))? {
+                let path = entry.map_err(err(&self.path))?.path();
+                let file = path.file_name().unwrap().to_string_lossy().to_string();
+                if is_modeled(&file) {
+                    continue;
+                }
+                if path.is_dir() {
+                    copy_dir(&path, &tmp.join(&file))?;
+                } else {
+                    fs::copy(&path, tmp.join(&file)).map_err(err(&path))?;
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&tmp);
+            return Err(e);
+        }
+        fs::rename(&self.path, &bak).map_err(err(&self.path))?;
+        if let Err(e) = fs::rename(&tmp, &self.path) {
+            let _ = fs::rename(&bak, &self.path);
+            return Err(err(&tmp)(e));
+        }
+        fs::remove_dir_all(&bak).map_err(err(&bak))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_and_save() {
+        let f = SaveFolder::open("t/3AA Blights Segment Start v2").unwrap();
+        assert_eq!(f.slots.len(), 6);
+        let c = &f.slot(0).unwrap().caption;
+        assert_eq!(c.location(), "HyruleCastle_Hall_0");
+        assert_eq!(c.district(), "MapArea_CentralHyrule");
+        assert_eq!(c.time(), 1625688851);
+        assert!(!c.hard_mode());
+        assert_eq!(f.album.len(), 34);
+        assert!(f.pict_book.contains_key("Enemy_Dragon_Electric.jpg"));
+        assert_eq!(f.tracker.normal.len(), 11);
+        assert_eq!(f.metadata.as_ref().unwrap().user_name, "SlyZorua");
+        assert!(f.warnings().is_empty());
+
+        let dir = std::env::temp_dir().join(format!("botw-folder-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        f.write(&dir).unwrap();
+        fs::write(dir.join("notes.txt"), "keep").unwrap();
+        let mut g = SaveFolder::open(&dir).unwrap();
+        g.slots.remove(1);
+        g.save().unwrap();
+        let h = SaveFolder::open(&dir).unwrap();
+        assert_eq!(h.slots.len(), 5);
+        assert!(h.slot(1).is_none());
+        assert_eq!(
+            fs::read(dir.join("0/game_data.sav")).unwrap(),
+            fs::read("t/3AA Blights Segment Start v2/0/game_data.sav").unwrap()
+        );
+        assert_eq!(fs::read_to_string(dir.join("notes.txt")).unwrap(), "keep");
+        let out = h.export(&dir, Layout::Jksv, "practice").unwrap();
+        assert!(out.ends_with("JKSV/The Legend of Zelda Breath of the Wild/SlyZorua - practice"));
+        assert!(out.join("0/caption.jpg").is_file());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn manage_slots() {
+        let mut f = SaveFolder::open("t/+99 cleanup").unwrap();
+        assert_eq!(f.kind(0), Some(SlotKind::Autosave));
+        assert_eq!(f.kind(5), Some(SlotKind::Manual));
+        assert_eq!(f.kind(7), Some(SlotKind::MasterMode));
+
+        let loc = f.slot(1).unwrap().caption.location();
+        f.copy_slot(1, 3).unwrap();
+        assert_eq!(f.slot(3).unwrap().caption.location(), loc);
+        assert!(f.copy_slot(1, 6).is_err());
+        assert!(f.copy_slot(6, 2).is_err());
+
+        let loc0 = f.slot(0).unwrap().caption.location();
+        f.swap_slots(0, 1).unwrap();
+        assert_eq!(f.slot(0).unwrap().caption.location(), loc);
+        assert_eq!(f.slot(1).unwrap().caption.location(), loc0);
+
+        assert!(f.clear_slot(4).is_some());
+        assert!(f.slot(4).is_none());
+        f.swap_slots(4, 2).unwrap();
+        assert!(f.slot(2).is_none());
+        assert!(f.slot(4).is_some());
+    }
+}
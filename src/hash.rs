@@ -1,4 +1,4 @@
-pub const KEYS: [&str; 43667] = [
+pub static KEYS: [&str; 43667] = [
 // "dummy",
 "MainField_Item_Fruit_A_1641432141",
 "GodTree_Finish",
@@ -0,0 +1,425 @@
+//! Pouch contents, `PorchItem` and the arrays that go with it
+//!
+//! `PorchItem`, `PorchItem_Value1` and `PorchItem_EquipFlag` are indexed by
+//! pouch slot.  The weapon bonus arrays `Porch{Sword,Bow,Shield}_{FlagSp,ValueSp}`
+//! are indexed by the weapon's position among weapons of its kind, the cooking
+//! arrays `CookEffect0/1`, `StaminaRecover` and `CookMaterialName0..4` by the
+//! position among food.  Items are kept grouped by pouch tab, as the game does.
+
+use crate::SaveData;
+
+const ITEMS: &str = "PorchItem";
+const VALUES: &str = "PorchItem_Value1";
+const EQUIPPED: &str = "PorchItem_EquipFlag";
+const EFFECT: &str = "CookEffect0";
+const EFFECT1: &str = "CookEffect1";
+const RECOVER: &str = "StaminaRecover";
+pub const MATERIALS: usize = 5;
+
+fn material_key(i: usize) -> String {
+    format!("CookMaterialName{}", i)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Category {
+    Sword,
+    Bow,
+    Arrow,
+    Shield,
+    ArmorHead,
+    ArmorUpper,
+    ArmorLower,
+    Material,
+    Food,
+    KeyItem,
+}
+
+impl Category {
+    /// Category of an item actor name
+    pub fn of(name: &str) -> Category {
+        if name.starts_with("Weapon_Sword_")
+            || name.starts_with("Weapon_Lsword_")
+            || name.starts_with("Weapon_Spear_")
+        {
+            Category::Sword
+        } else if name.starts_with("Weapon_Bow_") {
+            Category::Bow
+        } else if name.starts_with("Weapon_Shield_") {
+            Category::Shield
+        } else if name.ends_with("Arrow") || name.contains("Arrow_") {
+            Category::Arrow
+        } else if name.starts_with("Armor_") && name.contains("_Head") {
+            Category::ArmorHead
+        } else if name.starts_with("Armor_") && name.contains("_Upper") {
+            Category::ArmorUpper
+        } else if name.starts_with("Armor_") && name.contains("_Lower") {
+            Category::ArmorLower
+        } else if ["Item_Cook_", "Item_Roast", "Item_Boiled", "Item_Chilled"]
+            .iter()
+            .any(|p| name.starts_with(p))
+        {
+            Category::Food
+        } else if name.starts_with("Item_")
+            || name.starts_with("Animal_")
+            || name == "BeeHome"
+            || name == "Obj_FireWoodBundle"
+        {
+            Category::Material
+        } else {
+            Category::KeyItem
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Sword => "sword",
+            Category::Bow => "bow",
+            Category::Arrow => "arrow",
+            Category::Shield => "shield",
+            Category::ArmorHead => "armor_head",
+            Category::ArmorUpper => "armor_upper",
+            Category::ArmorLower => "armor_lower",
+            Category::Material => "material",
+            Category::Food => "food",
+            Category::KeyItem => "key_item",
+        }
+    }
+
+    /// Pouch tab, armor pieces share one and arrows follow the bows
+    fn tab(&self) -> u8 {
+        match self {
+            Category::Sword => 0,
+            Category::Bow => 1,
+            Category::Arrow => 2,
+            Category::Shield => 3,
+            Category::ArmorHead | Category::ArmorUpper | Category::ArmorLower => 4,
+            Category::Material => 5,
+            Category::Food => 6,
+            Category::KeyItem => 7,
+        }
+    }
+
+    /// Prefix of the bonus arrays, for weapons
+    fn bonus_key(&self) -> Option<&'static str> {
+        match self {
+            Category::Sword => Some("PorchSword"),
+            Category::Bow => Some("PorchBow"),
+            Category::Shield => Some("PorchShield"),
+            _ => None,
+        }
+    }
+
+    pub fn is_weapon(&self) -> bool {
+        self.bonus_key().is_some()
+    }
+}
+
+/// Raw `FlagSp` and `ValueSp` of a weapon
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bonus {
+    pub flags: i32,
+    pub value: i32,
+}
+
+/// Raw cooking data of a food item
+#[derive(Clone, Debug, PartialEq)]
+pub struct CookData {
+    /// `CookEffect0`, effect id and level
+    pub effect: [f32; 2],
+    /// `CookEffect1`, sell price
+    pub effect1: [f32; 2],
+    /// `StaminaRecover`, quarter hearts and effect time in seconds
+    pub recover: [f32; 2],
+    pub materials: [String; MATERIALS],
+}
+
+impl Default for CookData {
+    fn default() -> CookData {
+        CookData {
+            effect: [-1.0, 0.0],
+            effect1: [-1.0, 0.0],
+            recover: [-1.0, 0.0],
+            materials: Default::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InventoryItem {
+    pub name: String,
+    pub category: Category,
+    /// Quantity, or durability x 100 for weapons and shields
+    pub value: i32,
+    pub equipped: bool,
+    /// Weapons only
+    pub bonus: Option<Bonus>,
+    /// Food only
+    pub cook: Option<CookData>,
+}
+
+impl InventoryItem {
+    pub fn new(name: &str, value: i32) -> InventoryItem {
+        let category = Category::of(name);
+        InventoryItem {
+            name: name.to_string(),
+            category,
+            value,
+            equipped: false,
+            bonus: category.is_weapon().then(Bonus::default),
+            cook: (category == Category::Food).then(CookData::default),
+        }
+    }
+
+    pub fn durability(&self) -> Option<f32> {
+        self.category
+            .is_weapon()
+            .then_some(self.value as f32 / 100.0)
+    }
+    pub fn quantity(&self) -> Option<i32> {
+        (!self.category.is_weapon()).then_some(self.value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inventory {
+    pub items: Vec<InventoryItem>,
+    /// Array lengths, `PorchItem`, sword, bow and shield bonuses, food
+    capacity: [usize; 5],
+}
+
+fn pairs(v: Vec<f32>) -> Vec<[f32; 2]> {
+    v.chunks(2).map(|c| [c[0], c[1]]).collect()
+}
+
+impl Inventory {
+    pub fn read(save: &SaveData) -> Result<Inventory, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        let names = save.get_strs(ITEMS).ok_or(missing(ITEMS))?;
+        let values = save.get_s32s(VALUES).ok_or(missing(VALUES))?;
+        let equipped = save.get_bools(EQUIPPED).ok_or(missing(EQUIPPED))?;
+        let mut bonus = vec![];
+        for cat in [Category::Sword, Category::Bow, Category::Shield] {
+            let key = cat.bonus_key().unwrap();
+            let flags = save
+                .get_s32s(&format!("{}_FlagSp", key))
+                .ok_or(missing(key))?;
+            let values = save
+                .get_s32s(&format!("{}_ValueSp", key))
+                .ok_or(missing(key))?;
+            bonus.push((cat, flags, values));
+        }
+        let effect = pairs(save.get_f32s(EFFECT).ok_or(missing(EFFECT))?);
+        let effect1 = pairs(save.get_f32s(EFFECT1).ok_or(missing(EFFECT1))?);
+        let recover = pairs(save.get_f32s(RECOVER).ok_or(missing(RECOVER))?);
+        let mut materials = vec![];
+        for i in 0..MATERIALS {
+            materials.push(
+                save.get_strs(&material_key(i))
+                    .ok_or(missing(&material_key(i)))?,
+            );
+        }
+
+        let mut items = vec![];
+        let mut food = 0;
+        for (i, name) in names.iter().enumerate() {
+            if name.is_empty() {
+                break;
+            }
+            let mut item = InventoryItem::new(name, values[i]);
+            item.equipped = equipped[i];
+            if let Some((_, flags, values)) = bonus.iter().find(|b| b.0 == item.category) {
+                let n = items
+                    .iter()
+                    .filter(|it: &&InventoryItem| it.category == item.category)
+                    .count();
+                item.bonus = Some(Bonus {
+                    flags: *flags.get(n).unwrap_or(&0),
+                    value: *values.get(n).unwrap_or(&0),
+                });
+            }
+            if item.category == Category::Food && food < effect.len() {
+                item.cook = Some(CookData {
+                    effect: effect[food],
+                    effect1: effect1[food],
+                    recover: recover[food],
+                    materials: std::array::from_fn(|m| materials[m][food].clone()),
+                });
+                food += 1;
+            }
+            items.push(item);
+        }
+        Ok(Inventory {
+            items,
+            capacity: [
+                names.len(),
+                bonus[0].1.len(),
+                bonus[1].1.len(),
+                bonus[2].1.len(),
+                effect.len(),
+            ],
+        })
+    }
+
+    pub fn count(&self, category: Category) -> usize {
+        self.items.iter().filter(|i| i.category == category).count()
+    }
+
+    fn limit(&self, category: Category) -> usize {
+        match category {
+            Category::Sword => self.capacity[1],
+            Category::Bow => self.capacity[2],
+            Category::Shield => self.capacity[3],
+            Category::Food => self.capacity[4],
+            _ => self.capacity[0],
+        }
+    }
+
+    /// Add `item` after the last item of its pouch tab, returns its index
+    pub fn add(&mut self, item: InventoryItem) -> Result<usize, String> {
+        if self.items.len() >= self.capacity[0]
+            || self.count(item.category) >= self.limit(item.category)
+        {
+            return Err(format!("Error: no room for {}", item.name));
+        }
+        let tab = item.category.tab();
+        let index = self
+            .items
+            .iter()
+            .rposition(|i| i.category.tab() <= tab)
+            .map_or(0, |i| i + 1);
+        self.items.insert(index, item);
+        Ok(index)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<InventoryItem, String> {
+        if index >= self.items.len() {
+            return Err(format!("Error: no item {}", index));
+        }
+        Ok(self.items.remove(index))
+    }
+
+    /// Move item `from` to `to`, both must be in the same pouch tab
+    pub fn reorder(&mut self, from: usize, to: usize) -> Result<(), String> {
+        let (Some(a), Some(b)) = (self.items.get(from), self.items.get(to)) else {
+            return Err(format!("Error: no item {}", from.max(to)));
+        };
+        if a.category.tab() != b.category.tab() {
+            return Err(format!(
+                "Error: cannot move {} among {} items",
+                a.name,
+                b.category.name()
+            ));
+        }
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        Ok(())
+    }
+
+    /// Write every array, unused entries are cleared
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for cat in [
+            Category::Sword,
+            Category::Bow,
+            Category::Shield,
+            Category::Food,
+        ] {
+            if self.count(cat) > self.limit(cat) {
+                return Err(format!(
+                    "Error: pouch holds {} {} items",
+                    self.limit(cat),
+                    cat.name()
+                ));
+            }
+        }
+        let n = self.capacity[0];
+        if self.items.len() > n {
+            return Err(format!("Error: pouch holds {} items", n));
+        }
+        let mut names = vec![String::new(); n];
+        let mut values = vec![0; n];
+        let mut equipped = vec![false; n];
+        for (i, item) in self.items.iter().enumerate() {
+            names[i] = item.name.clone();
+            values[i] = item.value;
+            equipped[i] = item.equipped;
+        }
+        save.set_strs(ITEMS, &names)?;
+        save.set_s32s(VALUES, &values)?;
+        save.set_bools(EQUIPPED, &equipped)?;
+
+        for cat in [Category::Sword, Category::Bow, Category::Shield] {
+            let mut flags = vec![0; self.limit(cat)];
+            let mut values = vec![0; self.limit(cat)];
+            let bonus = self.items.iter().filter(|i| i.category == cat);
+            for (j, item) in bonus.enumerate() {
+                let b = item.bonus.unwrap_or_default();
+                flags[j] = b.flags;
+                values[j] = b.value;
+            }
+            let key = cat.bonus_key().unwrap();
+            save.set_s32s(&format!("{}_FlagSp", key), &flags)?;
+            save.set_s32s(&format!("{}_ValueSp", key), &values)?;
+        }
+
+        let mut cook = vec![CookData::default(); self.limit(Category::Food)];
+        let food = self.items.iter().filter(|i| i.category == Category::Food);
+        for (j, item) in food.enumerate() {
+            cook[j] = item.cook.clone().unwrap_or_default();
+        }
+        let flat = |f: fn(&CookData) -> [f32; 2]| -> Vec<f32> { cook.iter().flat_map(f).collect() };
+        save.set_f32s(EFFECT, &flat(|c| c.effect))?;
+        save.set_f32s(EFFECT1, &flat(|c| c.effect1))?;
+        save.set_f32s(RECOVER, &flat(|c| c.recover))?;
+        for m in 0..MATERIALS {
+            let names: Vec<_> = cook.iter().map(|c| c.materials[m].clone()).collect();
+            save.set_strs(&material_key(m), &names)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_pouch() {
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let mut inv = Inventory::read(&s).unwrap();
+        assert_eq!(inv.items.len(), 159);
+        assert_eq!(inv.count(Category::Sword), 16);
+        let it = &inv.items[11];
+        assert_eq!(it.name, "Weapon_Lsword_014");
+        assert!(it.equipped);
+        assert_eq!(it.durability(), Some(17.0));
+        assert_eq!(it.bonus.unwrap().value, 5);
+        let it = &inv.items[94];
+        assert_eq!(it.category, Category::Food);
+        assert_eq!(it.cook.as_ref().unwrap().effect, [13.0, 3.0]);
+        assert_eq!(inv.items[158].category, Category::KeyItem);
+
+        // Unchanged pouch writes back the same arrays
+        let mut t = s.clone();
+        inv.write(&mut t).unwrap();
+        assert_eq!(Inventory::read(&t).unwrap(), inv);
+
+        let bonus = inv.items[11].bonus;
+        inv.remove(0).unwrap();
+        assert_eq!(
+            inv.add(InventoryItem::new("Weapon_Sword_070", 3000)),
+            Ok(15)
+        );
+        assert_eq!(inv.add(InventoryItem::new("Item_Cook_C_17", 1)), Ok(144));
+        assert!(inv.reorder(0, 20).is_err());
+        inv.reorder(10, 0).unwrap();
+        inv.write(&mut s).unwrap();
+
+        let inv = Inventory::read(&s).unwrap();
+        assert_eq!(inv.items.len(), 160);
+        assert_eq!(inv.items[0].name, "Weapon_Lsword_014");
+        assert_eq!(inv.items[0].bonus, bonus);
+        assert_eq!(inv.items[15].bonus, Some(Bonus::default()));
+        assert_eq!(inv.items[144].cook, Some(CookData::default()));
+        assert_eq!(s.get_s32s("PorchSword_ValueSp").unwrap()[0], 5);
+    }
+}
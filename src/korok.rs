@@ -0,0 +1,246 @@
+//! Korok seeds, the `MainField_Npc_HiddenKorok{Ground,Fly}_<hash id>` flags
+//!
+//! There is one flag per seed, named after the hash id of the Korok actor
+//! that hands it over, which appears on the ground (`Ground`) or in the
+//! air (`Fly`) once its puzzle is solved.
+//! `HiddenKorok_Number` is the number of seeds found and drives Hestu's
+//! count, so it is rewritten with the flags.
+//!
+//! The save does not say where a seed is or what its puzzle is, and `Kind`
+//! is only how the Korok appears.  There is no built-in table of the 900
+//! seeds with puzzle, region and coordinates: nothing in this tree has
+//! that data, and a table typed in from memory would be wrong.  Until one
+//! is extracted from the game's map units, regions and coordinates are
+//! read from a CSV of `hash_id,region,x,y,z` rows, e.g. exported from a map
+//! viewer, and exports without one have no region or position.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use serde_json::{json, Value};
+
+use crate::hash::KEYS;
+use crate::SaveData;
+
+const PREFIX: &str = "MainField_Npc_HiddenKorok";
+const NUMBER: &str = "HiddenKorok_Number";
+pub const TOTAL: usize = 900;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Ground,
+    Fly,
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Ground => "Ground",
+            Kind::Fly => "Fly",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Seed {
+    pub hash_id: u32,
+    pub kind: Kind,
+    pub found: bool,
+}
+
+impl Seed {
+    pub fn flag(&self) -> String {
+        format!("{}{}_{}", PREFIX, self.kind.name(), self.hash_id)
+    }
+}
+
+/// Every seed as (hash id, kind), sorted by hash id
+pub fn seeds() -> Vec<(u32, Kind)> {
+    let mut v: Vec<_> = KEYS
+        .iter()
+        .filter_map(|k| {
+            let (kind, id) = k.strip_prefix(PREFIX)?.split_once('_')?;
+            let kind = match kind {
+                "Ground" => Kind::Ground,
+                "Fly" => Kind::Fly,
+                _ => return None,
+            };
+            Some((id.parse().ok()?, kind))
+        })
+        .collect();
+    v.sort_unstable();
+    v
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub region: String,
+    pub pos: [f32; 3],
+}
+
+/// Seed locations by hash id from `hash_id,region,x,y,z` rows, a header
+/// row is skipped
+pub fn read_locations(csv: &str) -> Result<BTreeMap<u32, Location>, String> {
+    let mut out = BTreeMap::new();
+    for (i, line) in csv.lines().enumerate() {
+        let f: Vec<_> = line.split(',').map(|v| v.trim()).collect();
+        if line.trim().is_empty() || (i == 0 && f[0].parse::<u32>().is_err()) {
+            continue;
+        }
+        let bad = || format!("Error: bad korok location on line {}: {}", i + 1, line);
+        if f.len() != 5 {
+            return Err(bad());
+        }
+        let id = f[0].parse().map_err(|_| bad())?;
+        let mut pos = [0.0; 3];
+        for (p, v) in pos.iter_mut().zip(&f[2..]) {
+            *p = v.parse().map_err(|_| bad())?;
+        }
+        out.insert(
+            id,
+            Location {
+                region: f[1].to_string(),
+                pos,
+            },
+        );
+    }
+    Ok(out)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Koroks {
+    pub seeds: Vec<Seed>,
+}
+
+impl Koroks {
+    pub fn read(save: &SaveData) -> Result<Koroks, String> {
+        let mut out = vec![];
+        for (hash_id, kind) in seeds() {
+            let mut seed = Seed {
+                hash_id,
+                kind,
+                found: false,
+            };
+            seed.found = save
+                .get_bool(&seed.flag())
+                .ok_or(format!("Error: save has no {}", seed.flag()))?;
+            out.push(seed);
+        }
+        Ok(Koroks { seeds: out })
+    }
+
+    /// Write the flags and the matching `HiddenKorok_Number`
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for seed in &self.seeds {
+            save.set_bool(&seed.flag(), seed.found)?;
+        }
+        save.set_s32(NUMBER, self.collected() as i32)
+    }
+
+    pub fn collected(&self) -> usize {
+        self.seeds.iter().filter(|s| s.found).count()
+    }
+
+    pub fn missing(&self) -> Vec<&Seed> {
+        self.seeds.iter().filter(|s| !s.found).collect()
+    }
+
+    pub fn set_found(&mut self, hash_id: u32, found: bool) -> Result<(), String> {
+        self.seeds
+            .iter_mut()
+            .find(|s| s.hash_id == hash_id)
+            .ok_or(format!("Error: no korok {}", hash_id))?
+            .found = found;
+        Ok(())
+    }
+
+    pub fn set_all(&mut self, found: bool) {
+        for seed in &mut self.seeds {
+            seed.found = found;
+        }
+    }
+
+    /// Missing seeds, with the region and position when known
+    pub fn missing_csv(&self, locations: &BTreeMap<u32, Location>) -> String {
+        let mut s = String::from("hash_id,type,region,x,y,z\n");
+        for seed in self.missing() {
+            let _ = match locations.get(&seed.hash_id) {
+                Some(l) => writeln!(
+                    s,
+                    "{},{},{},{},{},{}",
+                    seed.hash_id,
+                    seed.kind.name(),
+                    l.region,
+                    l.pos[0],
+                    l.pos[1],
+                    l.pos[2]
+                ),
+                None => writeln!(s, "{},{},,,,", seed.hash_id, seed.kind.name()),
+            };
+        }
+        s
+    }
+
+    /// Missing seeds with a known location as GeoJSON points in map space
+    pub fn missing_geojson(&self, locations: &BTreeMap<u32, Location>) -> Value {
+        let features: Vec<_> = self
+            .missing()
+            .into_iter()
+            .filter_map(|seed| {
+                let l = locations.get(&seed.hash_id)?;
+                Some(json!({
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [l.pos[0], l.pos[2]] },
+                    "properties": {
+                        "hash_id": seed.hash_id,
+                        "type": seed.kind.name(),
+                        "region": l.region,
+                        "y": l.pos[1],
+                    },
+                }))
+            })
+            .collect();
+        json!({ "type": "FeatureCollection", "features": features })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_and_export() {
+        let all = seeds();
+        assert_eq!(all.len(), TOTAL);
+        assert_eq!(all.iter().filter(|s| s.1 == Kind::Fly).count(), 333);
+
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let mut k = Koroks::read(&s).unwrap();
+        assert_eq!(k.collected(), 877);
+        assert_eq!(s.get_s32("HiddenKorok_Number"), Some(877));
+        let missing: Vec<_> = k.missing().iter().map(|s| s.hash_id).collect();
+        assert_eq!(missing.len(), 23);
+
+        let locs = read_locations(&format!(
+            "hash_id,region,x,y,z\n{},Akkala,3000.5,200,-2500\n",
+            missing[0]
+        ))
+        .unwrap();
+        assert!(read_locations("1,Akkala,1,2").is_err());
+        let csv = k.missing_csv(&locs);
+        assert_eq!(csv.lines().count(), 24);
+        assert!(csv.contains(&format!("{},", missing[1])));
+        let geo = k.missing_geojson(&locs);
+        assert_eq!(geo["features"].as_array().unwrap().len(), 1);
+        assert_eq!(geo["features"][0]["properties"]["region"], "Akkala");
+
+        k.set_found(missing[0], true).unwrap();
+        assert!(k.set_found(1, true).is_err());
+        k.write(&mut s).unwrap();
+        assert_eq!(s.get_s32("HiddenKorok_Number"), Some(878));
+        k.set_all(true);
+        k.write(&mut s).unwrap();
+        assert_eq!(Koroks::read(&s).unwrap().collected(), TOTAL);
+        assert_eq!(s.get_s32("HiddenKorok_Number"), Some(900));
+    }
+}
@@ -33,6 +33,32 @@ macro_rules! from_json {
 mod types;
 use types::*;
 
+pub mod hash;
+
+pub mod actor;
+pub mod album;
+pub mod beast;
+pub mod compendium;
+pub mod completion;
+pub mod dish;
+pub mod enemy;
+pub mod folder;
+pub mod inventory;
+pub mod korok;
+pub mod map;
+pub mod metadata;
+pub mod modifier;
+pub mod pin;
+pub mod player;
+pub mod quest;
+pub mod shrine;
+pub mod stable;
+pub mod teleport;
+pub mod tower;
+pub mod tracker;
+pub mod weather;
+pub mod world;
+
 #[cfg(target_arch = "wasm32")]
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 extern "C" {
@@ -58,7 +84,7 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct SaveData {
     version: u32,
@@ -68,6 +94,20 @@ pub struct SaveData {
     data: Vec<u8>,
 }
 
+const VERSIONS: [(u32, &str); 11] = [
+    (0x24e2, "1.0.0"),
+    (0x24ee, "1.1.0"),
+    (0x2588, "1.2.0"),
+    (0x29c0, "1.3.0"),
+    (0x2a46, "1.3.1"),
+    (0x2f8e, "kiosk"),
+    (0x3ef8, "1.3.3"),
+    (0x3ef9, "1.3.4"),
+    (0x471a, "1.4.0"),
+    (0x471b, "1.5.0"),
+    (0x471e, "1.6.0"),
+];
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum Kind {
     Bool = 0,
@@ -113,6 +153,16 @@ impl fmt::Display for Kind {
         write!(f, "{s}")
     }
 }
+impl Kind {
+    /// Bytes in one string of a string kind
+    fn size(&self) -> usize {
+        match self {
+            Kind::Str64 | Kind::Str64Array => 64,
+            Kind::Str256 | Kind::Str256Array => 256,
+            _ => 32,
+        }
+    }
+}
 impl From<&str> for Kind {
     fn from(s: &str) -> Kind {
         match s {
@@ -483,9 +533,31 @@ impl SaveData {
                     }
                 }
             }
-            Kind::Str | Kind::Str256 | Kind::Str256Array | Kind::Str64 | Kind::Str64Array => {
-                console_log!("unhandled kind");
-                return JsValue::UNDEFINED;
+            Kind::Str | Kind::Str64 | Kind::Str256 => {
+                let val: String = from_json!(value, "expected string");
+                if let Err(err) = write_string(&mut self.data, *off, &val, kind.size()) {
+                    console_log!("{}", err);
+                    return JsValue::UNDEFINED;
+                }
+            }
+            Kind::Str64Array | Kind::Str256Array => {
+                let val: Vec<String> = from_json!(value, "expected [string]");
+                let size = kind.size();
+                let n = self.vec_len(*off) * 4 / size;
+                if n != val.len() {
+                    console_log!(
+                        "expected [string] of length {}, got length {}",
+                        n,
+                        val.len()
+                    );
+                    return JsValue::UNDEFINED;
+                }
+                for (i, v) in val.iter().enumerate() {
+                    if let Err(err) = write_string(&mut self.data, *off + i * size * 2, v, size) {
+                        console_log!("{}", err);
+                        return JsValue::UNDEFINED;
+                    }
+                }
             }
             Kind::Unknown => {
                 console_log!("could not find {} in type for {}", hash, key);
@@ -624,9 +696,37 @@ impl SaveData {
                     }
                 }
             }
-            Kind::Str | Kind::Str256 | Kind::Str256Array | Kind::Str64 | Kind::Str64Array => {
-                println!("unhandled kind");
-                return Err(false);
+            Kind::Str | Kind::Str64 | Kind::Str256 => {
+                let val = value.as_str().ok_or(false)?;
+                if let Err(err) = write_string(&mut self.data, *off, val, kind.size()) {
+                    println!("{}", err);
+                    return Err(false);
+                }
+            }
+            Kind::Str64Array | Kind::Str256Array => {
+                let val = value
+                    .as_array()
+                    .ok_or(false)?
+                    .iter()
+                    .map(|v| v.as_str())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(false)?;
+                let size = kind.size();
+                let n = self.vec_len(*off) * 4 / size;
+                if n != val.len() {
+                    println!(
+                        "expected [string] of length {}, got length {}",
+                        n,
+                        val.len()
+                    );
+                    return Err(false);
+                }
+                for (i, v) in val.iter().enumerate() {
+                    if let Err(err) = write_string(&mut self.data, *off + i * size * 2, v, size) {
+                        println!("{}", err);
+                        return Err(false);
+                    }
+                }
             }
             Kind::Unknown => {
                 println!("could not find {} in type for {}", hash, key);
@@ -637,6 +737,249 @@ impl SaveData {
     }
 }
 
+// Typed access for the editors built on top of SaveData
+impl SaveData {
+    fn lookup(&self, key: &str) -> Option<(usize, u32, Kind)> {
+        let hash: u32 = get_hash(key);
+        let off = *self.off.get(&hash)?;
+        let kind = Kind::from(*TYPES.get(&hash).unwrap_or(&"bool"));
+        Some((off, hash, kind))
+    }
+
+    // Files without an entry in TYPES, e.g. caption.sav, are read by hash
+    pub(crate) fn raw_s32(&self, hash: u32) -> Option<i32> {
+        read_i32(&self.data, self.off.get(&hash)? + 4).ok()
+    }
+    pub(crate) fn raw_str(&self, hash: u32, size: usize) -> Option<String> {
+        let mut off = *self.off.get(&hash)?;
+        read_string(&self.data, &mut off, hash, size)
+    }
+
+    /// Game version that wrote the save, from the header
+    pub fn game_version(&self) -> Option<&'static str> {
+        VERSIONS
+            .iter()
+            .find(|(v, _)| *v == self.version)
+            .map(|(_, name)| *name)
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.off.contains_key(&get_hash(key))
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.lookup(key)? {
+            (off, _, Kind::Bool) => Some(read_i32(&self.data, off + 4).ok()? != 0),
+            _ => None,
+        }
+    }
+    pub fn get_s32(&self, key: &str) -> Option<i32> {
+        match self.lookup(key)? {
+            (off, _, Kind::S32) => read_i32(&self.data, off + 4).ok(),
+            _ => None,
+        }
+    }
+    pub fn get_f32(&self, key: &str) -> Option<f32> {
+        match self.lookup(key)? {
+            (off, _, Kind::F32) => read_f32(&self.data, off + 4).ok(),
+            _ => None,
+        }
+    }
+    pub fn get_bools(&self, key: &str) -> Option<Vec<bool>> {
+        match self.lookup(key)? {
+            (off, hash, Kind::BoolArray) => Some(self.get_vec_bool_internal(off, hash)),
+            _ => None,
+        }
+    }
+    pub fn get_s32s(&self, key: &str) -> Option<Vec<i32>> {
+        match self.lookup(key)? {
+            (off, hash, Kind::S32Array) => Some(self.get_vec_s32_internal(off, hash)),
+            _ => None,
+        }
+    }
+    /// Flattened values of f32 arrays and vectors
+    pub fn get_f32s(&self, key: &str) -> Option<Vec<f32>> {
+        match self.lookup(key)? {
+            (
+                off,
+                hash,
+                Kind::F32Array
+                | Kind::Vec2f
+                | Kind::Vec3f
+                | Kind::Vec4f
+                | Kind::Vec2fArray
+                | Kind::Vec3fArray,
+            ) => Some(self.get_vec_internal(off, hash)),
+            _ => None,
+        }
+    }
+    pub fn get_vec3f(&self, key: &str) -> Option<[f32; 3]> {
+        match self.lookup(key)? {
+            (off, hash, Kind::Vec3f) => to_vec3farray(self.get_vec_internal(off, hash))
+                .first()
+                .copied(),
+            _ => None,
+        }
+    }
+    pub fn get_vec3fs(&self, key: &str) -> Option<Vec<[f32; 3]>> {
+        match self.lookup(key)? {
+            (off, hash, Kind::Vec3fArray) => Some(to_vec3farray(self.get_vec_internal(off, hash))),
+            _ => None,
+        }
+    }
+    pub fn get_strs(&self, key: &str) -> Option<Vec<String>> {
+        let (off, hash, kind) = self.lookup(key)?;
+        let size = match kind {
+            Kind::Str64Array => 64,
+            Kind::Str256Array => 256,
+            _ => return None,
+        };
+        let mut off = off;
+        let mut out = vec![];
+        while let Some(s) = read_string(&self.data, &mut off, hash, size) {
+            out.push(s)
+        }
+        Some(out)
+    }
+
+    fn lookup_kind(&self, key: &str, kinds: &[Kind]) -> Result<usize, String> {
+        let (off, _, kind) = self
+            .lookup(key)
+            .ok_or(format!("Error: could not find {} in offsets", key))?;
+        if !kinds.contains(&kind) {
+            return Err(format!("Error: {} is {}", key, kind));
+        }
+        Ok(off)
+    }
+
+    pub fn set_bool(&mut self, key: &str, value: bool) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::Bool])?;
+        write_i32(&mut self.data, off + 4, value as i32).unwrap();
+        Ok(())
+    }
+    pub fn set_s32(&mut self, key: &str, value: i32) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::S32])?;
+        write_i32(&mut self.data, off + 4, value).unwrap();
+        Ok(())
+    }
+    pub fn set_f32(&mut self, key: &str, value: f32) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::F32])?;
+        write_f32(&mut self.data, off + 4, value).unwrap();
+        Ok(())
+    }
+    pub fn set_str(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::Str, Kind::Str64, Kind::Str256])?;
+        let size = self.lookup(key).unwrap().2.size();
+        write_string(&mut self.data, off, value, size)
+    }
+    // Strings in files without an entry in TYPES, e.g. caption.sav
+    pub(crate) fn set_raw_str(
+        &mut self,
+        hash: u32,
+        value: &str,
+        size: usize,
+    ) -> Result<(), String> {
+        let off = *self
+            .off
+            .get(&hash)
+            .ok_or(format!("Error: could not find {:#x} in offsets", hash))?;
+        write_string(&mut self.data, off, value, size)
+    }
+    pub fn set_bools(&mut self, key: &str, vals: &[bool]) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::BoolArray])?;
+        let n = self.vec_len(off);
+        if n != vals.len() {
+            return Err(format!(
+                "Error: expected [bool] of length {}, got length {}",
+                n,
+                vals.len()
+            ));
+        }
+        for (i, &v) in vals.iter().enumerate() {
+            write_i32(&mut self.data, (off + 4) + i * 8, v as i32).unwrap();
+        }
+        Ok(())
+    }
+    pub fn set_s32s(&mut self, key: &str, vals: &[i32]) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::S32Array])?;
+        let n = self.vec_len(off);
+        if n != vals.len() {
+            return Err(format!(
+                "Error: expected [s32] of length {}, got length {}",
+                n,
+                vals.len()
+            ));
+        }
+        for (i, &v) in vals.iter().enumerate() {
+            write_i32(&mut self.data, (off + 4) + i * 8, v).unwrap();
+        }
+        Ok(())
+    }
+    /// Flattened values of f32 arrays and vectors, as in `get_f32s`
+    pub fn set_f32s(&mut self, key: &str, vals: &[f32]) -> Result<(), String> {
+        let off = self.lookup_kind(
+            key,
+            &[
+                Kind::F32Array,
+                Kind::Vec2f,
+                Kind::Vec3f,
+                Kind::Vec4f,
+                Kind::Vec2fArray,
+                Kind::Vec3fArray,
+            ],
+        )?;
+        let n = self.vec_len(off);
+        if n != vals.len() {
+            return Err(format!(
+                "Error: expected [f32] of length {}, got length {}",
+                n,
+                vals.len()
+            ));
+        }
+        for (i, &v) in vals.iter().enumerate() {
+            write_f32(&mut self.data, (off + 4) + i * 8, v).unwrap();
+        }
+        Ok(())
+    }
+    pub fn set_strs(&mut self, key: &str, vals: &[String]) -> Result<(), String> {
+        let off = self.lookup_kind(key, &[Kind::Str64Array, Kind::Str256Array])?;
+        let size = self.lookup(key).unwrap().2.size();
+        let n = self.vec_len(off) * 4 / size;
+        if n != vals.len() {
+            return Err(format!(
+                "Error: expected [string{}] of length {}, got length {}",
+                size,
+                n,
+                vals.len()
+            ));
+        }
+        for (i, v) in vals.iter().enumerate() {
+            write_string(&mut self.data, off + i * size * 2, v, size)?;
+        }
+        Ok(())
+    }
+}
+
+// Strings are stored 4 bytes per entry and zero padded to `size`
+fn write_string(data: &mut [u8], off: usize, value: &str, size: usize) -> Result<(), String> {
+    let bytes = value.as_bytes();
+    if bytes.len() >= size {
+        return Err(format!(
+            "Error: \"{}\" is longer than {} bytes",
+            value,
+            size - 1
+        ));
+    }
+    for i in 0..size / 4 {
+        let mut v = [0u8; 4];
+        for (j, b) in v.iter_mut().enumerate() {
+            *b = *bytes.get(i * 4 + j).unwrap_or(&0);
+        }
+        data[off + i * 8 + 4..off + i * 8 + 8].copy_from_slice(&v);
+    }
+    Ok(())
+}
+
 fn read_string(data: &[u8], off: &mut usize, hash: u32, size: usize) -> Option<String> {
     let mut out = vec![];
     let mut toff = *off;
@@ -711,7 +1054,7 @@ fn read_f32(data: &[u8], off: usize) -> Result<f32, i32> {
 
 pub fn get_hash(s: &str) -> u32 {
     let func: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
-    return func.checksum(s.as_bytes());
+    func.checksum(s.as_bytes())
 }
 
 #[cfg(test)]
@@ -725,20 +1068,16 @@ mod tests {
         assert_eq!(get_hash("MainField_Item_Fruit_A_1641432141"), 195588);
         assert_eq!(get_hash("GodTree_Finish"), 408334);
 
-        assert_eq!(
-            s.get("MainField_Enemy_Lizalfos_Junior_1163152111")
-                .unwrap()
-                .as_bool()
-                .unwrap(),
-            false
-        );
-        assert_eq!(
-            s.get("MainField_DgnObj_DungeonEntranceSP_Far_1792025272")
-                .unwrap()
-                .as_bool()
-                .unwrap(),
-            false
-        );
+        assert!(!s
+            .get("MainField_Enemy_Lizalfos_Junior_1163152111")
+            .unwrap()
+            .as_bool()
+            .unwrap());
+        assert!(!s
+            .get("MainField_DgnObj_DungeonEntranceSP_Far_1792025272")
+            .unwrap()
+            .as_bool()
+            .unwrap());
         let tmp = s.get("PorchShield_FlagSp").unwrap();
         let v = tmp.as_array().unwrap();
         for k in v.iter() {
@@ -768,8 +1107,8 @@ mod tests {
             [-1.0, 0.0],
             [-1.0, 0.0],
         ];
-        for i in 0..20 {
-            assert_eq!(v[i].as_i64().unwrap(), 0);
+        for k in v.iter().take(20) {
+            assert_eq!(k.as_i64().unwrap(), 0);
         }
         let tmp = s.get("CookEffect0").unwrap();
         let v = tmp.as_array().unwrap();
@@ -0,0 +1,165 @@
+//! Hyrule map space and an SVG overlay of saved positions
+//!
+//! Map space is the same as `PlayerSavePos`: x grows to the east and z to
+//! the south, so it maps onto SVG user units without a transform.  The
+//! in-game grid has ten 1000m columns `A`..`J` and eight rows `1`..`8`.
+
+use std::fmt::Write as _;
+
+use crate::tracker::{Mode, Tracker};
+use crate::SaveData;
+
+pub const MIN_X: f32 = -5000.0;
+pub const MAX_X: f32 = 5000.0;
+pub const MIN_Z: f32 = -4000.0;
+pub const MAX_Z: f32 = 4000.0;
+const TILE: f32 = 1000.0;
+
+/// Grid tile name, e.g. `E-3`, of a MainField position
+pub fn tile_name(x: f32, z: f32) -> Option<String> {
+    if !(MIN_X..MAX_X).contains(&x) || !(MIN_Z..MAX_Z).contains(&z) {
+        return None;
+    }
+    let col = ((x - MIN_X) / TILE) as u8;
+    let row = ((z - MIN_Z) / TILE) as u8;
+    Some(format!("{}-{}", (b'A' + col) as char, row + 1))
+}
+
+// Unset positions are stored as zeros, unused map pins at x = -100000
+pub(crate) fn is_set(p: &[f32; 3]) -> bool {
+    *p != [0.0, 0.0, 0.0] && p[0] > -100000.0
+}
+
+const MARKERS: [(&str, &str, &str); 4] = [
+    ("PlayerSavePos", "Player", "#e03030"),
+    ("Horse_Pos", "Horse", "#a0522d"),
+    ("Last_Ridden_Horse_Pos", "Last ridden horse", "#d2a060"),
+    ("WarpDLC_DestPos", "Travel Medallion", "#30a0e0"),
+];
+
+/// Render the save's positions, map pins and Hero's Path as SVG
+pub fn svg(save: &SaveData, tracker: Option<&Tracker>) -> String {
+    let w = MAX_X - MIN_X;
+    let h = MAX_Z - MIN_Z;
+    let mut s = String::new();
+    let _ = writeln!(
+        s,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">",
+        MIN_X,
+        MIN_Z,
+        w,
+        h,
+        w / 10.0,
+        h / 10.0
+    );
+    let _ = writeln!(
+        s,
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#f4f0e0\"/>",
+        MIN_X, MIN_Z, w, h
+    );
+
+    s.push_str(
+        "<g id=\"grid\" stroke=\"#c8c0a8\" stroke-width=\"4\" font-size=\"80\" fill=\"#a09880\">\n",
+    );
+    for i in 0..=((w / TILE) as i32) {
+        let x = MIN_X + i as f32 * TILE;
+        let _ = writeln!(
+            s,
+            "<line x1=\"{x}\" y1=\"{MIN_Z}\" x2=\"{x}\" y2=\"{MAX_Z}\"/>"
+        );
+    }
+    for i in 0..=((h / TILE) as i32) {
+        let z = MIN_Z + i as f32 * TILE;
+        let _ = writeln!(
+            s,
+            "<line x1=\"{MIN_X}\" y1=\"{z}\" x2=\"{MAX_X}\" y2=\"{z}\"/>"
+        );
+    }
+    for i in 0..((w / TILE) as i32) {
+        for j in 0..((h / TILE) as i32) {
+            let x = MIN_X + i as f32 * TILE;
+            let z = MIN_Z + j as f32 * TILE;
+            if let Some(name) = tile_name(x, z) {
+                let _ = writeln!(
+                    s,
+                    "<text x=\"{}\" y=\"{}\" stroke=\"none\">{}</text>",
+                    x + 20.0,
+                    z + 90.0,
+                    name
+                );
+            }
+        }
+    }
+    s.push_str("</g>\n");
+
+    if let Some(t) = tracker {
+        for (mode, color) in [(Mode::Normal, "#2060c0"), (Mode::Master, "#c02060")] {
+            let _ = writeln!(
+                s,
+                "<g id=\"path-{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"6\">",
+                mode.name(),
+                color
+            );
+            for seg in t.segments(mode) {
+                s.push_str("<polyline points=\"");
+                for p in seg {
+                    let _ = write!(s, "{},{} ", p.x, p.z);
+                }
+                s.push_str("\"/>\n");
+            }
+            s.push_str("</g>\n");
+        }
+    }
+
+    if let (Some(pos), Some(no)) = (
+        save.get_vec3fs("MapApp_MapIconPos"),
+        save.get_s32s("MapApp_MapIconNo"),
+    ) {
+        s.push_str("<g id=\"pins\" fill=\"#f0c020\" stroke=\"#604000\" stroke-width=\"4\" font-size=\"60\">\n");
+        for (p, n) in pos.iter().zip(no.iter()) {
+            if *n < 0 || !is_set(p) {
+                continue;
+            }
+            let _ = writeln!(
+                s,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"25\"><title>Pin {}</title></circle>",
+                p[0], p[2], n
+            );
+        }
+        s.push_str("</g>\n");
+    }
+
+    s.push_str("<g id=\"markers\" stroke=\"#000000\" stroke-width=\"6\">\n");
+    for (key, label, color) in MARKERS {
+        if let Some(p) = save.get_vec3f(key).filter(is_set) {
+            let _ = writeln!(
+                s,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"40\" fill=\"{}\"><title>{}</title></circle>",
+                p[0], p[2], color, label
+            );
+        }
+    }
+    s.push_str("</g>\n");
+    s.push_str("</svg>\n");
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_and_overlay() {
+        assert_eq!(tile_name(-1094.9, -2653.4).unwrap(), "D-2");
+        assert_eq!(tile_name(3374.9, 2194.9).unwrap(), "I-7");
+        assert_eq!(tile_name(6000.0, 0.0), None);
+
+        let s = SaveData::read("t/+99 IST/6/game_data.sav").unwrap();
+        let t = Tracker::read("t/+99 IST/tracker").unwrap();
+        let out = svg(&s, Some(&t));
+        assert!(out.contains("<title>Player</title>"));
+        assert!(out.contains("<title>Pin 42</title>"));
+        assert!(out.contains("<g id=\"path-master\""));
+        assert!(out.contains("<title>Travel Medallion</title>"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Switch save manager metadata and backup layouts
+//!
+//! EdiZon writes `edizon_save_metadata.json` next to the save files,
+//! JKSV and Checkpoint only encode the title and user in the backup path.
+
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+pub const FILENAME: &str = "edizon_save_metadata.json";
+pub const TITLE_ID: &str = "01007EF00011E000";
+pub const TITLE_NAME: &str = "The Legend of Zelda: Breath of the Wild";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    pub title_id: String,
+    pub title_name: String,
+    pub title_version: String,
+    pub user_id: String,
+    pub user_name: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// `switch/EdiZon/saves/<title id> <title>/<name>/`
+    EdiZon,
+    /// `JKSV/<title>/<user> - <name>/`
+    Jksv,
+    /// `switch/Checkpoint/saves/0x<title id> <title>/<name> <user>/`
+    Checkpoint,
+}
+
+// Characters the SD card's FAT file system does not allow
+fn safe(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, ':' | '*' | '?' | '"' | '<' | '>' | '|' | '/' | '\\'))
+        .collect()
+}
+
+impl Metadata {
+    pub fn new(title_version: &str, user_id: &str, user_name: &str) -> Metadata {
+        Metadata {
+            title_id: TITLE_ID.to_string(),
+            title_name: TITLE_NAME.to_string(),
+            title_version: title_version.to_string(),
+            user_id: user_id.to_string(),
+            user_name: user_name.to_string(),
+        }
+    }
+
+    pub fn from_json(text: &[u8]) -> Result<Metadata, String> {
+        let v: Value =
+            serde_json::from_slice(text).map_err(|e| format!("Error: bad metadata: {}", e))?;
+        let field = |k: &str| -> Result<String, String> {
+            v[k].as_str()
+                .map(String::from)
+                .ok_or(format!("Error: metadata is missing {}", k))
+        };
+        Ok(Metadata {
+            title_id: field("title_id")?,
+            title_name: field("title_name")?,
+            title_version: field("title_version")?,
+            user_id: field("user_id")?,
+            user_name: field("user_name")?,
+        })
+    }
+
+    /// Same layout and indentation as EdiZon
+    pub fn to_json(&self) -> String {
+        let fields = [
+            ("title_id", &self.title_id),
+            ("title_name", &self.title_name),
+            ("title_version", &self.title_version),
+            ("user_id", &self.user_id),
+            ("user_name", &self.user_name),
+        ];
+        let lines: Vec<_> = fields
+            .iter()
+            .map(|(k, v)| format!("    \"{}\": {}", k, json!(v)))
+            .collect();
+        format!("{{\n{}\n}}\n", lines.join(",\n"))
+    }
+
+    pub fn read(filename: &str) -> Result<Metadata, String> {
+        let text = std::fs::read(filename).map_err(|e| format!("Error: {}: {}", filename, e))?;
+        Metadata::from_json(&text)
+    }
+    pub fn write(&self, filename: &str) -> Result<(), String> {
+        std::fs::write(filename, self.to_json()).map_err(|e| format!("Error: {}: {}", filename, e))
+    }
+
+    /// Backup directory for `layout`, relative to the SD card root
+    pub fn backup_dir(&self, layout: Layout, name: &str) -> PathBuf {
+        let title = safe(&self.title_name);
+        let user = safe(&self.user_name);
+        let name = safe(name);
+        match layout {
+            Layout::EdiZon => PathBuf::from("switch/EdiZon/saves")
+                .join(format!("{} {}", self.title_id, title))
+                .join(name),
+            Layout::Jksv => PathBuf::from("JKSV")
+                .join(title)
+                .join(format!("{} - {}", user, name)),
+            Layout::Checkpoint => PathBuf::from("switch/Checkpoint/saves")
+                .join(format!("0x{} {}", self.title_id, title))
+                .join(format!("{} {}", name, user)),
+        }
+    }
+
+    /// Warning when `title_version` is not the version that wrote the save
+    pub fn version_warning(&self, save_version: Option<&str>) -> Option<String> {
+        match save_version {
+            None => Some(format!(
+                "Warning: unknown game_data.sav version, metadata says {}",
+                self.title_version
+            )),
+            Some(v) if v != self.title_version => Some(format!(
+                "Warning: game_data.sav is version {}, metadata says {}",
+                v, self.title_version
+            )),
+            Some(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let name = "t/3AA Blights Segment Start v2/edizon_save_metadata.json";
+        let m = Metadata::read(name).unwrap();
+        assert_eq!(m.user_name, "SlyZorua");
+        assert_eq!(m.title_version, "1.6.0");
+        assert_eq!(m.to_json(), std::fs::read_to_string(name).unwrap());
+        assert_eq!(m.version_warning(Some("1.6.0")), None);
+        assert!(m.version_warning(Some("1.5.0")).is_some());
+        let old = Metadata::new("1.3.3", &m.user_id, &m.user_name);
+        assert!(old.version_warning(Some("1.3.4")).is_some());
+        assert_eq!(
+            m.backup_dir(Layout::Checkpoint, "20210707-201640"),
+            PathBuf::from("switch/Checkpoint/saves/0x01007EF00011E000 The Legend of Zelda Breath of the Wild/20210707-201640 SlyZorua")
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Weapon bonus modifiers, `FlagSp` bit flags with a shared `ValueSp`
+//!
+//! The pouch stores them in `Porch{Sword,Bow,Shield}_{FlagSp,ValueSp}`, the
+//! weapon stands in Link's house in `EquipStand{Sword,Bow,Shield}_*`.  The
+//! high bit marks the stronger "yellow" (+) variant of a modifier.
+
+use std::ops::RangeInclusive;
+
+use crate::inventory::{Bonus, Category};
+use crate::SaveData;
+
+const YELLOW: u32 = 0x8000_0000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    AttackUp,
+    DurabilityUp,
+    CriticalHit,
+    LongThrow,
+    MultiShot,
+    Zoom,
+    QuickShot,
+    ShieldSurfUp,
+    GuardUp,
+    AttackUpPlus,
+    DurabilityUpPlus,
+    CriticalHitPlus,
+    LongThrowPlus,
+    MultiShotPlus,
+    ZoomPlus,
+    QuickShotPlus,
+    ShieldSurfUpPlus,
+    GuardUpPlus,
+}
+
+const MODIFIERS: [Modifier; 9] = [
+    Modifier::AttackUp,
+    Modifier::DurabilityUp,
+    Modifier::CriticalHit,
+    Modifier::LongThrow,
+    Modifier::MultiShot,
+    Modifier::Zoom,
+    Modifier::QuickShot,
+    Modifier::ShieldSurfUp,
+    Modifier::GuardUp,
+];
+
+impl Modifier {
+    /// Bit in `FlagSp`, without the yellow bit
+    pub fn flag(&self) -> u32 {
+        match self.white() {
+            Modifier::AttackUp => 0x1,
+            Modifier::DurabilityUp => 0x2,
+            Modifier::CriticalHit => 0x4,
+            Modifier::LongThrow => 0x8,
+            Modifier::MultiShot => 0x10,
+            Modifier::Zoom => 0x20,
+            Modifier::QuickShot => 0x40,
+            Modifier::ShieldSurfUp => 0x80,
+            _ => 0x100,
+        }
+    }
+
+    pub fn is_yellow(&self) -> bool {
+        *self != self.white()
+    }
+
+    pub fn white(&self) -> Modifier {
+        match self {
+            Modifier::AttackUpPlus => Modifier::AttackUp,
+            Modifier::DurabilityUpPlus => Modifier::DurabilityUp,
+            Modifier::CriticalHitPlus => Modifier::CriticalHit,
+            Modifier::LongThrowPlus => Modifier::LongThrow,
+            Modifier::MultiShotPlus => Modifier::MultiShot,
+            Modifier::ZoomPlus => Modifier::Zoom,
+            Modifier::QuickShotPlus => Modifier::QuickShot,
+            Modifier::ShieldSurfUpPlus => Modifier::ShieldSurfUp,
+            Modifier::GuardUpPlus => Modifier::GuardUp,
+            m => *m,
+        }
+    }
+
+    pub fn yellow(&self) -> Modifier {
+        match self.white() {
+            Modifier::AttackUp => Modifier::AttackUpPlus,
+            Modifier::DurabilityUp => Modifier::DurabilityUpPlus,
+            Modifier::CriticalHit => Modifier::CriticalHitPlus,
+            Modifier::LongThrow => Modifier::LongThrowPlus,
+            Modifier::MultiShot => Modifier::MultiShotPlus,
+            Modifier::Zoom => Modifier::ZoomPlus,
+            Modifier::QuickShot => Modifier::QuickShotPlus,
+            Modifier::ShieldSurfUp => Modifier::ShieldSurfUpPlus,
+            _ => Modifier::GuardUpPlus,
+        }
+    }
+
+    /// Name as shown in game
+    pub fn name(&self) -> &'static str {
+        match self {
+            Modifier::AttackUp => "Attack Up",
+            Modifier::DurabilityUp => "Durability Up",
+            Modifier::CriticalHit => "Critical Hit",
+            Modifier::LongThrow => "Long Throw",
+            Modifier::MultiShot => "Multi-Shot",
+            Modifier::Zoom => "Zoom",
+            Modifier::QuickShot => "Quick Shot",
+            Modifier::ShieldSurfUp => "Shield Surf Up",
+            Modifier::GuardUp => "Guard Up",
+            Modifier::AttackUpPlus => "Attack Up+",
+            Modifier::DurabilityUpPlus => "Durability Up+",
+            Modifier::CriticalHitPlus => "Critical Hit+",
+            Modifier::LongThrowPlus => "Long Throw+",
+            Modifier::MultiShotPlus => "Multi-Shot+",
+            Modifier::ZoomPlus => "Zoom+",
+            Modifier::QuickShotPlus => "Quick Shot+",
+            Modifier::ShieldSurfUpPlus => "Shield Surf Up+",
+            Modifier::GuardUpPlus => "Guard Up+",
+        }
+    }
+
+    /// Values the game rolls for this modifier on `category`, None when
+    /// the modifier does not exist for it
+    pub fn range(&self, category: Category) -> Option<RangeInclusive<i32>> {
+        use Category::*;
+        let r = match (self.white(), category) {
+            (Modifier::AttackUp, Sword | Bow) => 1..=60,
+            (Modifier::DurabilityUp, Sword | Bow | Shield) => 1..=50,
+            // The value is unused, the game writes 0 or 1
+            (Modifier::CriticalHit, Sword) => 0..=1,
+            // Throw distance x 1000
+            (Modifier::LongThrow, Sword) => 1000..=2000,
+            // Arrows per shot
+            (Modifier::MultiShot, Bow) => 2..=5,
+            // Zoom and draw speed x 1000
+            (Modifier::Zoom | Modifier::QuickShot, Bow) => 1000..=3000,
+            // Surf speed x 1000
+            (Modifier::ShieldSurfUp, Shield) => 1000..=3000,
+            (Modifier::GuardUp, Shield) => 1..=60,
+            _ => return None,
+        };
+        Some(r)
+    }
+}
+
+impl Bonus {
+    pub fn new(modifiers: &[Modifier], value: i32) -> Bonus {
+        let mut flags = 0;
+        for m in modifiers {
+            flags |= m.flag();
+            if m.is_yellow() {
+                flags |= YELLOW;
+            }
+        }
+        Bonus {
+            flags: flags as i32,
+            value,
+        }
+    }
+
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        let flags = self.flags as u32;
+        MODIFIERS
+            .iter()
+            .filter(|m| flags & m.flag() != 0)
+            .map(|m| if flags & YELLOW != 0 { m.yellow() } else { *m })
+            .collect()
+    }
+
+    /// Check the flags and value are ones the game gives to `category`
+    pub fn validate(&self, category: Category) -> Result<(), String> {
+        let flags = self.flags as u32;
+        let known = MODIFIERS.iter().fold(YELLOW, |f, m| f | m.flag());
+        if flags & !known != 0 {
+            return Err(format!("Error: unknown modifier flags {:#x}", flags));
+        }
+        let mods = self.modifiers();
+        if mods.is_empty() {
+            if flags != 0 || self.value != 0 {
+                return Err(format!(
+                    "Error: no modifier for flags {:#x} and value {}",
+                    flags, self.value
+                ));
+            }
+            return Ok(());
+        }
+        if mods.len() > 1 {
+            let names: Vec<_> = mods.iter().map(|m| m.name()).collect();
+            return Err(format!(
+                "Error: more than one modifier: {}",
+                names.join(", ")
+            ));
+        }
+        let m = mods[0];
+        match m.range(category) {
+            None => Err(format!(
+                "Error: {} is not a {} modifier",
+                m.name(),
+                category.name()
+            )),
+            Some(r) if !r.contains(&self.value) => Err(format!(
+                "Error: {} value {} is outside {}..={}",
+                m.name(),
+                self.value,
+                r.start(),
+                r.end()
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// A weapon on one of the stands in Link's house
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stand {
+    pub name: String,
+    /// Durability x 100
+    pub life: i32,
+    pub bonus: Bonus,
+}
+
+fn stand_key(category: Category) -> Result<&'static str, String> {
+    match category {
+        Category::Sword => Ok("EquipStandSword"),
+        Category::Bow => Ok("EquipStandBow"),
+        Category::Shield => Ok("EquipStandShield"),
+        _ => Err(format!("Error: no {} stands", category.name())),
+    }
+}
+
+/// Weapon stands for `category`, empty stands have an empty name
+pub fn stands(save: &SaveData, category: Category) -> Result<Vec<Stand>, String> {
+    let key = stand_key(category)?;
+    let missing = || format!("Error: save has no {} stands", category.name());
+    let names = save
+        .get_strs(&format!("{}_Slot", key))
+        .ok_or_else(missing)?;
+    let life = save
+        .get_s32s(&format!("{}_Life_Slot", key))
+        .ok_or_else(missing)?;
+    let flags = save
+        .get_s32s(&format!("{}_FlagSp", key))
+        .ok_or_else(missing)?;
+    let values = save
+        .get_s32s(&format!("{}_ValueSp", key))
+        .ok_or_else(missing)?;
+    Ok(names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Stand {
+            name,
+            life: life[i],
+            bonus: Bonus {
+                flags: flags[i],
+                value: values[i],
+            },
+        })
+        .collect())
+}
+
+pub fn set_stands(save: &mut SaveData, category: Category, stands: &[Stand]) -> Result<(), String> {
+    let key = stand_key(category)?;
+    let names: Vec<_> = stands.iter().map(|s| s.name.clone()).collect();
+    let life: Vec<_> = stands.iter().map(|s| s.life).collect();
+    let flags: Vec<_> = stands.iter().map(|s| s.bonus.flags).collect();
+    let values: Vec<_> = stands.iter().map(|s| s.bonus.value).collect();
+    save.set_strs(&format!("{}_Slot", key), &names)?;
+    save.set_s32s(&format!("{}_Life_Slot", key), &life)?;
+    save.set_s32s(&format!("{}_FlagSp", key), &flags)?;
+    save.set_s32s(&format!("{}_ValueSp", key), &values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::Inventory;
+
+    #[test]
+    fn decode_and_validate() {
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let inv = Inventory::read(&s).unwrap();
+        let b = inv.items[12].bonus.unwrap();
+        assert_eq!(b.modifiers(), vec![Modifier::LongThrowPlus]);
+        assert_eq!(b, Bonus::new(&[Modifier::LongThrowPlus], 1657));
+        assert!(b.validate(Category::Sword).is_ok());
+        assert!(b.validate(Category::Shield).is_err());
+        assert!(inv.items[13]
+            .bonus
+            .unwrap()
+            .validate(Category::Sword)
+            .is_ok());
+
+        // Every bow modifier at once
+        let b = inv.items[16].bonus.unwrap();
+        assert_eq!(b.modifiers().len(), 7);
+        assert!(b.validate(Category::Bow).is_err());
+        assert!(Bonus::new(&[Modifier::MultiShot], 5)
+            .validate(Category::Bow)
+            .is_ok());
+        assert!(Bonus::new(&[Modifier::MultiShot], 9)
+            .validate(Category::Bow)
+            .is_err());
+
+        let mut st = stands(&s, Category::Sword).unwrap();
+        assert_eq!(st.len(), 10);
+        st[0] = Stand {
+            name: "Weapon_Spear_034".to_string(),
+            life: 4000,
+            bonus: b,
+        };
+        set_stands(&mut s, Category::Sword, &st).unwrap();
+        assert_eq!(stands(&s, Category::Sword).unwrap(), st);
+        assert!(stands(&s, Category::Food).is_err());
+    }
+}
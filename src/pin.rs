@@ -0,0 +1,244 @@
+//! Map pins and stamps, `MapApp_MapIconNo` and `MapApp_MapIconPos`
+//!
+//! Both arrays have one entry per map marker.  `MapApp_MapIconNo` holds
+//! the stamp drawn at the position in `MapApp_MapIconPos`; free entries are
+//! -1 at x = -100000.  `MapApp_PointGuidePoint` is the map's guide point.
+//!
+//! Pins are imported from CSV rows of `x,y,z` with an optional fourth
+//! `stamp` column, or from GeoJSON points with `[x, z]` coordinates and the
+//! height in a `y` property, as written by the korok export.
+
+use serde_json::Value;
+
+use crate::map::is_set;
+use crate::SaveData;
+
+const ICON_NO: &str = "MapApp_MapIconNo";
+const ICON_POS: &str = "MapApp_MapIconPos";
+const GUIDE: &str = "MapApp_PointGuidePoint";
+
+const EMPTY_NO: i32 = -1;
+const EMPTY_POS: [f32; 3] = [-100000.0, 0.0, 0.0];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pin {
+    pub stamp: i32,
+    pub pos: [f32; 3],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pins {
+    pub pins: Vec<Pin>,
+    /// Number of marker slots in the save
+    pub slots: usize,
+    pub guide: [f32; 3],
+}
+
+impl Pins {
+    pub fn read(save: &SaveData) -> Result<Pins, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        let no = save.get_s32s(ICON_NO).ok_or(missing(ICON_NO))?;
+        let pos = save.get_vec3fs(ICON_POS).ok_or(missing(ICON_POS))?;
+        if no.len() != pos.len() {
+            return Err(format!(
+                "Error: {} has {} entries, {} has {}",
+                ICON_NO,
+                no.len(),
+                ICON_POS,
+                pos.len()
+            ));
+        }
+        let pins = no
+            .iter()
+            .zip(&pos)
+            .filter(|(n, p)| **n >= 0 && is_set(p))
+            .map(|(&stamp, &pos)| Pin { stamp, pos })
+            .collect();
+        Ok(Pins {
+            pins,
+            slots: no.len(),
+            guide: save.get_vec3f(GUIDE).ok_or(missing(GUIDE))?,
+        })
+    }
+
+    /// Write the pins to the first slots and free the rest
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        if self.pins.len() > self.slots {
+            return Err(format!(
+                "Error: {} map pins do not fit in {} slots",
+                self.pins.len(),
+                self.slots
+            ));
+        }
+        let mut no = vec![EMPTY_NO; self.slots];
+        let mut pos = vec![EMPTY_POS; self.slots];
+        for (i, pin) in self.pins.iter().enumerate() {
+            no[i] = pin.stamp;
+            pos[i] = pin.pos;
+        }
+        save.set_s32s(ICON_NO, &no)?;
+        save.set_f32s(ICON_POS, pos.concat().as_slice())?;
+        save.set_f32s(GUIDE, &self.guide)
+    }
+
+    /// Pins with `stamp`, or all of them
+    pub fn list(&self, stamp: Option<i32>) -> Vec<&Pin> {
+        self.pins
+            .iter()
+            .filter(|p| stamp.is_none_or(|s| p.stamp == s))
+            .collect()
+    }
+
+    pub fn add(&mut self, stamp: i32, pos: [f32; 3]) -> Result<(), String> {
+        if stamp < 0 {
+            return Err(format!("Error: bad stamp {}", stamp));
+        }
+        if !is_set(&pos) {
+            return Err(format!("Error: bad pin position {:?}", pos));
+        }
+        if self.pins.len() >= self.slots {
+            return Err(format!("Error: all {} map pins are used", self.slots));
+        }
+        self.pins.push(Pin { stamp, pos });
+        Ok(())
+    }
+
+    /// Move the `index`th pin
+    pub fn move_to(&mut self, index: usize, pos: [f32; 3]) -> Result<(), String> {
+        if !is_set(&pos) {
+            return Err(format!("Error: bad pin position {:?}", pos));
+        }
+        self.pins
+            .get_mut(index)
+            .ok_or(format!("Error: no map pin {}", index))?
+            .pos = pos;
+        Ok(())
+    }
+
+    /// Remove the pins with `stamp`, or all of them, returning how many
+    pub fn clear(&mut self, stamp: Option<i32>) -> usize {
+        let n = self.pins.len();
+        self.pins.retain(|p| stamp.is_some_and(|s| p.stamp != s));
+        n - self.pins.len()
+    }
+
+    /// Add every pin, nothing is added if they do not all fit
+    pub fn extend(&mut self, pins: Vec<Pin>) -> Result<(), String> {
+        if self.pins.len() + pins.len() > self.slots {
+            return Err(format!(
+                "Error: {} map pins do not fit, {} of {} are used",
+                pins.len(),
+                self.pins.len(),
+                self.slots
+            ));
+        }
+        for pin in pins {
+            self.add(pin.stamp, pin.pos)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pins from `x,y,z[,stamp]` rows, `stamp` for rows without one; a header
+/// row is skipped
+pub fn read_csv(csv: &str, stamp: i32) -> Result<Vec<Pin>, String> {
+    let mut out = vec![];
+    for (i, line) in csv.lines().enumerate() {
+        let f: Vec<_> = line.split(',').map(|v| v.trim()).collect();
+        if line.trim().is_empty() || (i == 0 && f[0].parse::<f32>().is_err()) {
+            continue;
+        }
+        let bad = || format!("Error: bad map pin on line {}: {}", i + 1, line);
+        if f.len() != 3 && f.len() != 4 {
+            return Err(bad());
+        }
+        let mut pos = [0.0; 3];
+        for (p, v) in pos.iter_mut().zip(&f) {
+            *p = v.parse().map_err(|_| bad())?;
+        }
+        let stamp = match f.get(3) {
+            Some(s) => s.parse().map_err(|_| bad())?,
+            None => stamp,
+        };
+        out.push(Pin { stamp, pos });
+    }
+    Ok(out)
+}
+
+/// Pins from the points of a GeoJSON feature collection, with the stamp
+/// from a `stamp` property or `stamp`
+pub fn read_geojson(geo: &Value, stamp: i32) -> Result<Vec<Pin>, String> {
+    let features = geo["features"]
+        .as_array()
+        .ok_or("Error: GeoJSON has no features")?;
+    let mut out = vec![];
+    for (i, f) in features.iter().enumerate() {
+        if f["geometry"]["type"] != "Point" {
+            continue;
+        }
+        let c = &f["geometry"]["coordinates"];
+        let (Some(x), Some(z)) = (c[0].as_f64(), c[1].as_f64()) else {
+            return Err(format!("Error: bad coordinates in feature {}", i));
+        };
+        let props = &f["properties"];
+        let y = props["y"].as_f64().unwrap_or(0.0);
+        out.push(Pin {
+            stamp: props["stamp"].as_i64().map_or(stamp, |s| s as i32),
+            pos: [x as f32, y as f32, z as f32],
+        });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn edit_and_import() {
+        let mut s = SaveData::read("t/+99 IST/6/game_data.sav").unwrap();
+        let mut p = Pins::read(&s).unwrap();
+        assert_eq!(p.slots, 200);
+        assert_eq!(p.list(None).len(), 1);
+        assert_eq!(p.list(Some(42))[0].pos[2], 3489.9177);
+        assert_eq!(p.guide, [-560.0, 0.0, 1695.0]);
+
+        let csv = "x,y,z,stamp\n100,20,-300\n-50.5,0,10,3\n";
+        p.extend(read_csv(csv, 1).unwrap()).unwrap();
+        assert!(read_csv("1,2", 0).is_err());
+        let geo = json!({ "type": "FeatureCollection", "features": [
+            { "type": "Feature",
+              "geometry": { "type": "Point", "coordinates": [1200.0, -800.0] },
+              "properties": { "y": 150.0 } },
+        ]});
+        p.extend(read_geojson(&geo, 3).unwrap()).unwrap();
+        assert_eq!(p.list(Some(3)).len(), 2);
+        assert!(p.add(-1, [0.0, 0.0, 1.0]).is_err());
+        assert!(p.move_to(9, [1.0, 0.0, 1.0]).is_err());
+        p.move_to(0, [10.0, 0.0, 20.0]).unwrap();
+        p.guide = [1200.0, 150.0, -800.0];
+        assert_eq!(p.clear(Some(1)), 1);
+        p.write(&mut s).unwrap();
+
+        let mut p = Pins::read(&s).unwrap();
+        assert_eq!(p.pins.len(), 3);
+        assert_eq!(p.pins[0].pos, [10.0, 0.0, 20.0]);
+        assert_eq!(p.pins[2].pos, [1200.0, 150.0, -800.0]);
+        assert_eq!(s.get_vec3f("MapApp_PointGuidePoint"), Some(p.guide));
+        assert_eq!(p.clear(None), 3);
+        p.write(&mut s).unwrap();
+        let mut full = p.clone();
+        let pin = Pin {
+            stamp: 0,
+            pos: [1.0, 0.0, 1.0],
+        };
+        full.pins = vec![pin; full.slots + 1];
+        assert!(full.write(&mut s).is_err());
+        assert!(s
+            .get_s32s("MapApp_MapIconNo")
+            .unwrap()
+            .iter()
+            .all(|&n| n == -1));
+    }
+}
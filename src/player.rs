@@ -0,0 +1,189 @@
+//! Link's hearts, stamina, rupees and play time
+//!
+//! Hearts are stored in quarters, `CurrentHart` goes over `MaxHartValue`
+//! while Link has yellow hearts.  Stamina is 1000 per wheel.
+//! Play time is in seconds.
+
+use crate::SaveData;
+
+const CURRENT_HEARTS: &str = "CurrentHart";
+const MAX_HEARTS: &str = "MaxHartValue";
+const STAMINA_MAX: &str = "StaminaMax";
+const STAMINA_CURRENT_MAX: &str = "StaminaCurrentMax";
+const RUPEES: &str = "CurrentRupee";
+const PLAY_TIME: &str = "PlayReport_PlayTime";
+
+pub const MIN_HEARTS: u32 = 1;
+pub const MAX_HEARTS_LIMIT: u32 = 30;
+/// Most yellow hearts a dish can give
+pub const MAX_EXTRA_HEARTS: u32 = 25;
+pub const MIN_WHEELS: f32 = 1.0;
+pub const MAX_WHEELS: f32 = 3.0;
+/// Stamina vessels add a fifth of a wheel
+const WHEEL_STEP: f32 = 0.2;
+const WHEEL: f32 = 1000.0;
+pub const MAX_RUPEES: u32 = 999_999;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Player {
+    /// Quarter hearts
+    pub current_hearts: i32,
+    pub max_hearts: i32,
+    pub stamina_max: f32,
+    pub stamina_current_max: f32,
+    pub rupees: i32,
+    /// Seconds
+    pub play_time: i32,
+}
+
+impl Player {
+    pub fn read(save: &SaveData) -> Result<Player, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        Ok(Player {
+            current_hearts: save
+                .get_s32(CURRENT_HEARTS)
+                .ok_or(missing(CURRENT_HEARTS))?,
+            max_hearts: save.get_s32(MAX_HEARTS).ok_or(missing(MAX_HEARTS))?,
+            stamina_max: save.get_f32(STAMINA_MAX).ok_or(missing(STAMINA_MAX))?,
+            stamina_current_max: save
+                .get_f32(STAMINA_CURRENT_MAX)
+                .ok_or(missing(STAMINA_CURRENT_MAX))?,
+            rupees: save.get_s32(RUPEES).ok_or(missing(RUPEES))?,
+            play_time: save.get_s32(PLAY_TIME).ok_or(missing(PLAY_TIME))?,
+        })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        save.set_s32(CURRENT_HEARTS, self.current_hearts)?;
+        save.set_s32(MAX_HEARTS, self.max_hearts)?;
+        save.set_f32(STAMINA_MAX, self.stamina_max)?;
+        save.set_f32(STAMINA_CURRENT_MAX, self.stamina_current_max)?;
+        save.set_s32(RUPEES, self.rupees)?;
+        save.set_s32(PLAY_TIME, self.play_time)
+    }
+
+    /// Current hearts, including yellow hearts, e.g. 2.75
+    pub fn hearts(&self) -> f32 {
+        self.current_hearts as f32 / 4.0
+    }
+    pub fn max_hearts(&self) -> u32 {
+        (self.max_hearts / 4) as u32
+    }
+    pub fn yellow_hearts(&self) -> f32 {
+        (self.current_hearts - self.max_hearts).max(0) as f32 / 4.0
+    }
+
+    /// Set current hearts in quarters, above the max are yellow hearts
+    pub fn set_hearts(&mut self, hearts: f32) -> Result<(), String> {
+        let quarters = hearts * 4.0;
+        let limit = self.max_hearts + 4 * MAX_EXTRA_HEARTS as i32;
+        if quarters.fract() != 0.0 || quarters < 1.0 || quarters as i32 > limit {
+            return Err(format!(
+                "Error: hearts {} must be quarters from 0.25 to {}",
+                hearts,
+                limit as f32 / 4.0
+            ));
+        }
+        self.current_hearts = quarters as i32;
+        Ok(())
+    }
+
+    /// Set the heart containers, current hearts are refilled if they were
+    /// full and capped otherwise
+    pub fn set_max_hearts(&mut self, n: u32) -> Result<(), String> {
+        if !(MIN_HEARTS..=MAX_HEARTS_LIMIT).contains(&n) {
+            return Err(format!(
+                "Error: max hearts {} is outside {} to {}",
+                n, MIN_HEARTS, MAX_HEARTS_LIMIT
+            ));
+        }
+        let max = 4 * n as i32;
+        if self.current_hearts >= self.max_hearts || self.current_hearts > max {
+            self.current_hearts = max;
+        }
+        self.max_hearts = max;
+        Ok(())
+    }
+
+    pub fn stamina_wheels(&self) -> f32 {
+        self.stamina_max / WHEEL
+    }
+
+    /// Set the stamina wheels, in fifths of a wheel from 1 to 3
+    pub fn set_stamina_wheels(&mut self, n: f32) -> Result<(), String> {
+        let steps = n / WHEEL_STEP;
+        if !(MIN_WHEELS..=MAX_WHEELS).contains(&n) || (steps - steps.round()).abs() > 1e-4 {
+            return Err(format!(
+                "Error: stamina wheels {} must be fifths from {} to {}",
+                n, MIN_WHEELS, MAX_WHEELS
+            ));
+        }
+        self.stamina_max = (steps.round() * WHEEL_STEP * WHEEL).round();
+        self.stamina_current_max = self.stamina_max;
+        Ok(())
+    }
+
+    pub fn rupees(&self) -> u32 {
+        self.rupees.max(0) as u32
+    }
+
+    pub fn set_rupees(&mut self, n: u32) -> Result<(), String> {
+        if n > MAX_RUPEES {
+            return Err(format!("Error: rupees {} is more than {}", n, MAX_RUPEES));
+        }
+        self.rupees = n as i32;
+        Ok(())
+    }
+
+    /// Play time in seconds
+    pub fn play_time(&self) -> u32 {
+        self.play_time.max(0) as u32
+    }
+
+    pub fn set_play_time(&mut self, seconds: u32) -> Result<(), String> {
+        self.play_time = i32::try_from(seconds)
+            .map_err(|_| format!("Error: play time {} seconds is too long", seconds))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hearts_stamina_rupees() {
+        let mut s = SaveData::read("t/+99 IST/6/game_data.sav").unwrap();
+        let mut p = Player::read(&s).unwrap();
+        assert_eq!(
+            (p.hearts(), p.max_hearts(), p.yellow_hearts()),
+            (9.0, 3, 6.0)
+        );
+        assert_eq!(p.stamina_wheels(), 1.0);
+        assert_eq!(p.rupees(), 658);
+        assert_eq!(p.play_time(), 2854);
+
+        assert!(p.set_max_hearts(31).is_err());
+        p.set_max_hearts(13).unwrap();
+        assert_eq!(p.hearts(), 13.0);
+        p.set_hearts(2.75).unwrap();
+        assert!(p.set_hearts(2.8).is_err());
+        p.set_max_hearts(2).unwrap();
+        assert_eq!(p.hearts(), 2.0);
+        assert!(p.set_stamina_wheels(3.1).is_err());
+        assert!(p.set_stamina_wheels(1.5).is_err());
+        p.set_stamina_wheels(1.6).unwrap();
+        assert!(p.set_rupees(1_000_000).is_err());
+        p.set_rupees(999_999).unwrap();
+        assert!(p.set_play_time(u32::MAX).is_err());
+        p.set_play_time(3600).unwrap();
+        p.write(&mut s).unwrap();
+
+        let p = Player::read(&s).unwrap();
+        assert_eq!(s.get_s32("MaxHartValue"), Some(8));
+        assert_eq!(s.get_f32("StaminaMax"), Some(1600.0));
+        assert_eq!(s.get_f32("StaminaCurrentMax"), Some(1600.0));
+        assert_eq!(p.rupees(), 999_999);
+        assert_eq!(s.get_s32("PlayReport_PlayTime"), Some(3600));
+    }
+}
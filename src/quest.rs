@@ -0,0 +1,287 @@
+//! Quests, the `<Quest>_Ready`, `_Activated` and `_Finish` flag families
+//!
+//! A quest is `Ready` once it can be started, `Activated` while it shows
+//! in the Adventure Log and finished with `_Finish` (`_Finished` for a
+//! few).  Numbered `<Quest>_StepN` flags mark its progress in order, any
+//! other `<Quest>_*` flag is quest specific state and is only cleared on a
+//! reset.  Developer test quests are left out.
+
+use std::collections::HashSet;
+
+use crate::hash::KEYS;
+use crate::SaveData;
+
+const STATES: [&str; 4] = ["_Ready", "_Activated", "_Finish", "_Finished"];
+
+/// Main quests, including The Champions' Ballad
+const MAIN: [&str; 19] = [
+    "FirstOhenro",
+    "FirstTower",
+    "Find_Impa",
+    "Hateno_SheikPad_PowerUp",
+    "PictureMemory",
+    "FairyFountain",
+    "Find_4Relic",
+    "GotoZoraVillage",
+    "Water_Relic",
+    "Fire_Relic",
+    "Wind_Relic",
+    "Electric_Relic",
+    "Get_MasterSword",
+    "GanonQuest",
+    "BalladOfHeroes",
+    "BalladOfHeroGerudo",
+    "BalladOfHeroGoron",
+    "BalladOfHeroRito",
+    "BalladOfHeroZora",
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Main,
+    Side,
+    /// Leads to a shrine, has a `<Quest>_Dungeon` flag
+    Shrine,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    NotStarted,
+    Ready,
+    /// Activated with this many steps done
+    Activated(usize),
+    Finished,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quest {
+    pub name: String,
+    pub kind: Kind,
+    pub ready: bool,
+    pub activated: bool,
+    pub finished: bool,
+    finish_key: &'static str,
+    /// `StepN` flags in order
+    pub steps: Vec<(String, bool)>,
+    /// Other flags of the quest
+    pub extras: Vec<(String, bool)>,
+}
+
+fn step_number(flag: &str) -> Option<u32> {
+    flag.strip_prefix("Step")
+        .or_else(|| flag.strip_prefix("step"))?
+        .parse()
+        .ok()
+}
+
+/// Names of all quests, sorted
+pub fn names() -> Vec<&'static str> {
+    let mut v: Vec<_> = KEYS
+        .iter()
+        .filter_map(|k| STATES.iter().find_map(|s| k.strip_suffix(s)))
+        .filter(|q| !q.to_lowercase().contains("test"))
+        .collect();
+    v.sort_unstable();
+    v.dedup();
+    v
+}
+
+impl Quest {
+    pub fn state(&self) -> State {
+        if self.finished {
+            State::Finished
+        } else if self.activated {
+            State::Activated(self.steps.iter().take_while(|s| s.1).count())
+        } else if self.ready {
+            State::Ready
+        } else {
+            State::NotStarted
+        }
+    }
+
+    /// Set the quest to `state`, keeping the flags that lead to it set
+    pub fn set_state(&mut self, state: State) -> Result<(), String> {
+        let done = match state {
+            State::Activated(n) if n > self.steps.len() => {
+                return Err(format!(
+                    "Error: {} has {} steps",
+                    self.name,
+                    self.steps.len()
+                ))
+            }
+            State::Activated(n) => n,
+            State::Finished => self.steps.len(),
+            _ => 0,
+        };
+        if state == State::NotStarted {
+            for e in &mut self.extras {
+                e.1 = false;
+            }
+        }
+        self.ready = state != State::NotStarted;
+        self.activated = matches!(state, State::Activated(_) | State::Finished);
+        self.finished = state == State::Finished;
+        for (i, s) in self.steps.iter_mut().enumerate() {
+            s.1 = i < done;
+        }
+        Ok(())
+    }
+
+    /// Move one state or step forward
+    pub fn advance(&mut self) -> Result<(), String> {
+        let next = match self.state() {
+            State::NotStarted => State::Ready,
+            State::Ready => State::Activated(0),
+            State::Activated(n) if n < self.steps.len() => State::Activated(n + 1),
+            State::Activated(_) => State::Finished,
+            State::Finished => return Err(format!("Error: {} is finished", self.name)),
+        };
+        self.set_state(next)
+    }
+
+    pub fn reset(&mut self) {
+        self.set_state(State::NotStarted).unwrap();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quests {
+    pub quests: Vec<Quest>,
+}
+
+impl Quests {
+    pub fn read(save: &SaveData) -> Result<Quests, String> {
+        let names = names();
+        let set: HashSet<_> = names.iter().copied().collect();
+        let dungeon: HashSet<_> = KEYS
+            .iter()
+            .filter_map(|k| k.strip_suffix("_Dungeon"))
+            .collect();
+        let mut quests: Vec<_> = names
+            .iter()
+            .map(|&name| Quest {
+                name: name.to_string(),
+                kind: if MAIN.contains(&name) {
+                    Kind::Main
+                } else if dungeon.contains(name) {
+                    Kind::Shrine
+                } else {
+                    Kind::Side
+                },
+                ready: false,
+                activated: false,
+                finished: false,
+                finish_key: if save.has(&format!("{}_Finish", name)) {
+                    "_Finish"
+                } else {
+                    "_Finished"
+                },
+                steps: vec![],
+                extras: vec![],
+            })
+            .collect();
+        for key in KEYS.iter() {
+            // The longest quest name the key starts with
+            let quest = key
+                .rmatch_indices('_')
+                .map(|(i, _)| &key[..i])
+                .find(|q| set.contains(q));
+            let Some(quest) = quest else { continue };
+            let Some(value) = save.get_bool(key) else {
+                continue;
+            };
+            let q = &mut quests[names.binary_search(&quest).unwrap()];
+            let flag = &key[quest.len()..];
+            match flag {
+                "_Ready" => q.ready = value,
+                "_Activated" => q.activated = value,
+                f if f == q.finish_key => q.finished = value,
+                _ if step_number(&flag[1..]).is_some() => q.steps.push((key.to_string(), value)),
+                _ => q.extras.push((key.to_string(), value)),
+            }
+        }
+        for q in &mut quests {
+            let n = q.name.len() + 1;
+            q.steps.sort_by_key(|s| step_number(&s.0[n..]));
+            q.extras.sort();
+        }
+        Ok(Quests { quests })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for q in &self.quests {
+            let mut flags = vec![
+                (format!("{}_Ready", q.name), q.ready),
+                (format!("{}_Activated", q.name), q.activated),
+                (format!("{}{}", q.name, q.finish_key), q.finished),
+            ];
+            flags.extend(q.steps.iter().chain(&q.extras).cloned());
+            for (key, value) in flags {
+                // Not every quest has all three state flags
+                if save.has(&key) {
+                    save.set_bool(&key, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Result<&mut Quest, String> {
+        self.quests
+            .iter_mut()
+            .find(|q| q.name == name)
+            .ok_or(format!("Error: no quest {}", name))
+    }
+
+    pub fn of_kind(&self, kind: Kind) -> Vec<&Quest> {
+        self.quests.iter().filter(|q| q.kind == kind).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_and_reset() {
+        let mut s = SaveData::read("t/3AA Blights Segment Start v2/0/game_data.sav").unwrap();
+        let mut qs = Quests::read(&s).unwrap();
+        assert!(!names().contains(&"TestQuest_Takano_01"));
+        assert_eq!(qs.of_kind(Kind::Main).len(), MAIN.len());
+        assert!(qs
+            .of_kind(Kind::Shrine)
+            .iter()
+            .any(|q| q.name == "SearchStone"));
+        assert_eq!(
+            qs.get_mut("FirstTower").unwrap().state(),
+            State::Activated(0)
+        );
+
+        // Only Step2 is in the save
+        let q = qs.get_mut("FirstOhenro").unwrap();
+        assert_eq!(q.state(), State::Ready);
+        assert_eq!(q.steps.len(), 1);
+        q.advance().unwrap();
+        q.advance().unwrap();
+        assert_eq!(q.state(), State::Activated(1));
+        assert!(q.set_state(State::Activated(2)).is_err());
+
+        let q = qs.get_mut("Find_4Relic").unwrap();
+        q.set_state(State::Finished).unwrap();
+        assert!(q.advance().is_err());
+        q.extras[0].1 = true;
+        let q = qs.get_mut("GanonQuest").unwrap();
+        q.set_state(State::Finished).unwrap();
+        qs.write(&mut s).unwrap();
+
+        let mut qs = Quests::read(&s).unwrap();
+        assert_eq!(s.get_bool("FirstOhenro_Step2"), Some(true));
+        assert_eq!(s.get_bool("GanonQuest_Finished"), Some(true));
+        assert_eq!(s.get_bool("Find_4Relic_1stClear"), Some(true));
+        let q = qs.get_mut("Find_4Relic").unwrap();
+        assert_eq!(q.state(), State::Finished);
+        q.reset();
+        assert!(q.extras.iter().all(|e| !e.1));
+        assert!(!q.ready);
+    }
+}
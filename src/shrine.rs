@@ -0,0 +1,211 @@
+//! Shrines, `Dungeon000` to `Dungeon119` and the DLC's `Dungeon120` to
+//! `Dungeon135`
+//!
+//! Each shrine has a flag group named after it:
+//!
+//! ```text
+//! Location_DungeonNNN          s32, times the location name was shown
+//! Open_DungeonNNN              entrance activated
+//! Enter_DungeonNNN             entered
+//! Clear_DungeonNNN             completed
+//! CompleteTreasure_DungeonNNN  every chest inside opened
+//! DungeonNNN_Appear            risen, for the shrines a quest reveals
+//! CDungeonNNN_NetaClear        puzzle solved, for a few shrines
+//! ```
+//!
+//! Only a few shrines have the last two flags.  `Dungeon136` has flags
+//! too, but is not one of the shrines.  Shrines are known by these names
+//! only, the save has no display names.  Chests and other actors inside
+//! are `CDungeon_*` actor flags by hash id, and shrine quests are
+//! `<Quest>_Dungeon` flags; the save does not say which shrine either
+//! belongs to, so completing a shrine leaves them alone.  It leaves
+//! `CompleteTreasure` alone too, the chests it stands for stay closed.
+
+use crate::SaveData;
+
+pub const BASE: u32 = 120;
+pub const DLC: u32 = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shrine {
+    pub number: u32,
+    pub visits: i32,
+    pub open: bool,
+    pub entered: bool,
+    pub cleared: bool,
+    pub treasure: bool,
+    /// `DungeonNNN_Appear`, None for shrines without it
+    pub appeared: Option<bool>,
+    /// `CDungeonNNN_NetaClear`, None for shrines without it
+    pub solved: Option<bool>,
+}
+
+/// Shrine number from `Dungeon039` or `39`
+pub fn number(name: &str) -> Option<u32> {
+    let n: u32 = name.strip_prefix("Dungeon").unwrap_or(name).parse().ok()?;
+    (n < BASE + DLC).then_some(n)
+}
+
+fn flag(prefix: &str, number: u32) -> String {
+    format!("{}_Dungeon{:03}", prefix, number)
+}
+
+fn appear_flag(number: u32) -> String {
+    format!("Dungeon{:03}_Appear", number)
+}
+
+fn solved_flag(number: u32) -> String {
+    format!("CDungeon{:03}_NetaClear", number)
+}
+
+impl Shrine {
+    /// Map and message name, e.g. `Dungeon039`
+    pub fn name(&self) -> String {
+        format!("Dungeon{:03}", self.number)
+    }
+    pub fn is_dlc(&self) -> bool {
+        self.number >= BASE
+    }
+    pub fn discovered(&self) -> bool {
+        self.visits > 0
+    }
+
+    /// Set the flags the game sets when the shrine is finished
+    pub fn complete(&mut self) {
+        self.visits = self.visits.max(1);
+        self.open = true;
+        self.entered = true;
+        self.cleared = true;
+        self.appeared = self.appeared.map(|_| true);
+        self.solved = self.solved.map(|_| true);
+    }
+
+    /// Back to undiscovered
+    pub fn reset(&mut self) {
+        self.visits = 0;
+        self.open = false;
+        self.entered = false;
+        self.cleared = false;
+        self.treasure = false;
+        self.appeared = self.appeared.map(|_| false);
+        self.solved = self.solved.map(|_| false);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shrines {
+    pub shrines: Vec<Shrine>,
+}
+
+impl Shrines {
+    /// Read all shrines, DLC shrines are skipped in saves without them
+    pub fn read(save: &SaveData) -> Result<Shrines, String> {
+        let mut shrines = vec![];
+        for number in 0..BASE + DLC {
+            let key = flag("Location", number);
+            let visits = match save.get_s32(&key) {
+                Some(v) => v,
+                None if number >= BASE => continue,
+                None => return Err(format!("Error: save has no {}", key)),
+            };
+            let get = |prefix: &str| {
+                let key = flag(prefix, number);
+                save.get_bool(&key)
+                    .ok_or(format!("Error: save has no {}", key))
+            };
+            shrines.push(Shrine {
+                number,
+                visits,
+                open: get("Open")?,
+                entered: get("Enter")?,
+                cleared: get("Clear")?,
+                treasure: get("CompleteTreasure")?,
+                appeared: save.get_bool(&appear_flag(number)),
+                solved: save.get_bool(&solved_flag(number)),
+            });
+        }
+        Ok(Shrines { shrines })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for s in &self.shrines {
+            save.set_s32(&flag("Location", s.number), s.visits)?;
+            save.set_bool(&flag("Open", s.number), s.open)?;
+            save.set_bool(&flag("Enter", s.number), s.entered)?;
+            save.set_bool(&flag("Clear", s.number), s.cleared)?;
+            save.set_bool(&flag("CompleteTreasure", s.number), s.treasure)?;
+            if let Some(appeared) = s.appeared {
+                save.set_bool(&appear_flag(s.number), appeared)?;
+            }
+            if let Some(solved) = s.solved {
+                save.set_bool(&solved_flag(s.number), solved)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shrine by name, `Dungeon039`, or number
+    pub fn get_mut(&mut self, name: &str) -> Result<&mut Shrine, String> {
+        let n = number(name).ok_or(format!("Error: no shrine {}", name))?;
+        self.shrines
+            .iter_mut()
+            .find(|s| s.number == n)
+            .ok_or(format!("Error: save has no shrine {}", name))
+    }
+
+    pub fn complete(&mut self, name: &str) -> Result<(), String> {
+        self.get_mut(name)?.complete();
+        Ok(())
+    }
+
+    pub fn completed(&self) -> usize {
+        self.shrines.iter().filter(|s| s.cleared).count()
+    }
+
+    pub fn missing(&self) -> Vec<&Shrine> {
+        self.shrines.iter().filter(|s| !s.cleared).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_and_complete() {
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let mut sh = Shrines::read(&s).unwrap();
+        assert_eq!(sh.shrines.len(), 136);
+        let base: Vec<_> = sh.missing().into_iter().filter(|s| !s.is_dlc()).collect();
+        let names: Vec<_> = base.iter().map(|s| s.name()).collect();
+        assert_eq!(
+            names,
+            ["Dungeon018", "Dungeon042", "Dungeon062", "Dungeon115"]
+        );
+        // Standing inside, not finished yet
+        assert!(base[0].discovered() && base[0].entered && !base[0].cleared);
+        assert_eq!(sh.completed(), 116);
+        let solved: Vec<_> = sh.shrines.iter().filter(|s| s.solved.is_some()).collect();
+        assert_eq!(solved.len(), 2);
+        assert!(solved.iter().all(|s| s.solved == Some(true)));
+        assert_eq!(sh.shrines[109].appeared, Some(true));
+        assert!(sh.shrines[0].open);
+
+        assert!(sh.complete("Dungeon136").is_err());
+        assert!(number("Hateno").is_none());
+        sh.complete("Dungeon018").unwrap();
+        sh.complete("121").unwrap();
+        sh.get_mut("Dungeon079").unwrap().reset();
+        sh.get_mut("Dungeon109").unwrap().reset();
+        sh.write(&mut s).unwrap();
+
+        let sh = Shrines::read(&s).unwrap();
+        assert_eq!(s.get_bool("Clear_Dungeon121"), Some(true));
+        assert_eq!(s.get_s32("Location_Dungeon121"), Some(1));
+        assert_eq!(s.get_bool("Open_Dungeon121"), Some(true));
+        assert_eq!(s.get_bool("CompleteTreasure_Dungeon079"), Some(false));
+        assert_eq!(s.get_bool("CompleteTreasure_Dungeon018"), Some(false));
+        assert_eq!(s.get_bool("Dungeon109_Appear"), Some(false));
+        assert_eq!(sh.completed(), 116);
+    }
+}
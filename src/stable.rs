@@ -0,0 +1,264 @@
+//! Registered horses, the `Horse_*` and `DeadHorse_*` arrays
+//!
+//! The first five `Horse_*` entries are the stable slots, the sixth is the
+//! unregistered horse Link last rode.  `DeadHorse_*` holds horses that died
+//! while registered, and the Horse God can bring back.
+
+use crate::SaveData;
+
+pub const SLOTS: usize = 5;
+const SADDLE: &str = "GameRomHorseSaddle_";
+const REINS: &str = "GameRomHorseReins_";
+/// `Horse_Familiarity` ranges up to 100
+pub const MAX_BOND: f32 = 100.0;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Horse {
+    pub actor: String,
+    pub name: String,
+    pub mane: String,
+    pub saddle: String,
+    pub reins: String,
+    pub familiarity: f32,
+    pub foot_type: i32,
+    pub collar_type: i32,
+    /// Seconds ridden
+    pub ride_time: i32,
+    pub amiibo: String,
+    /// Only kept for living horses
+    pub familiarity_checked: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadHorse {
+    pub horse: Horse,
+    pub cause: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stable {
+    /// Stable slots, None when empty
+    pub horses: Vec<Option<Horse>>,
+    /// Last ridden unregistered horse
+    pub wild: Option<Horse>,
+    pub dead: Vec<DeadHorse>,
+    dead_slots: usize,
+}
+
+fn read_horses(save: &SaveData, prefix: &str) -> Result<Vec<Horse>, String> {
+    let strs = |k: &str| {
+        save.get_strs(&format!("{}_{}", prefix, k))
+            .ok_or(format!("Error: save has no {}_{}", prefix, k))
+    };
+    let s32s = |k: &str| {
+        save.get_s32s(&format!("{}_{}", prefix, k))
+            .ok_or(format!("Error: save has no {}_{}", prefix, k))
+    };
+    let actor = strs("ActorName")?;
+    let name = strs("UserName")?;
+    let mane = strs("ManeName")?;
+    let saddle = strs("SaddleName")?;
+    let reins = strs("ReinsName")?;
+    let amiibo = strs("AmiiboUidHash")?;
+    let foot = s32s("FootType")?;
+    let collar = s32s("CollarType")?;
+    let time = s32s("RideTimeSec")?;
+    let familiarity = save
+        .get_f32s(&format!("{}_Familiarity", prefix))
+        .ok_or(format!("Error: save has no {}_Familiarity", prefix))?;
+    let checked = save
+        .get_bools(&format!("{}_IsFamiliarityChecked", prefix))
+        .unwrap_or_default();
+    Ok((0..actor.len())
+        .map(|i| Horse {
+            actor: actor[i].clone(),
+            name: name[i].clone(),
+            mane: mane[i].clone(),
+            saddle: saddle[i].clone(),
+            reins: reins[i].clone(),
+            familiarity: familiarity[i],
+            foot_type: foot[i],
+            collar_type: collar[i],
+            ride_time: time[i],
+            amiibo: amiibo[i].clone(),
+            familiarity_checked: *checked.get(i).unwrap_or(&false),
+        })
+        .collect())
+}
+
+fn write_horses(save: &mut SaveData, prefix: &str, horses: &[Horse]) -> Result<(), String> {
+    let key = |k: &str| format!("{}_{}", prefix, k);
+    let strs =
+        |f: fn(&Horse) -> &String| -> Vec<String> { horses.iter().map(|h| f(h).clone()).collect() };
+    save.set_strs(&key("ActorName"), &strs(|h| &h.actor))?;
+    save.set_strs(&key("UserName"), &strs(|h| &h.name))?;
+    save.set_strs(&key("ManeName"), &strs(|h| &h.mane))?;
+    save.set_strs(&key("SaddleName"), &strs(|h| &h.saddle))?;
+    save.set_strs(&key("ReinsName"), &strs(|h| &h.reins))?;
+    save.set_strs(&key("AmiiboUidHash"), &strs(|h| &h.amiibo))?;
+    let s32s = |f: fn(&Horse) -> i32| -> Vec<i32> { horses.iter().map(f).collect() };
+    save.set_s32s(&key("FootType"), &s32s(|h| h.foot_type))?;
+    save.set_s32s(&key("CollarType"), &s32s(|h| h.collar_type))?;
+    save.set_s32s(&key("RideTimeSec"), &s32s(|h| h.ride_time))?;
+    let familiarity: Vec<_> = horses.iter().map(|h| h.familiarity).collect();
+    save.set_f32s(&key("Familiarity"), &familiarity)?;
+    if save.has(&key("IsFamiliarityChecked")) {
+        let checked: Vec<_> = horses.iter().map(|h| h.familiarity_checked).collect();
+        save.set_bools(&key("IsFamiliarityChecked"), &checked)?;
+    }
+    Ok(())
+}
+
+fn some(h: Horse) -> Option<Horse> {
+    (!h.actor.is_empty()).then_some(h)
+}
+
+impl Stable {
+    pub fn read(save: &SaveData) -> Result<Stable, String> {
+        let mut horses: Vec<_> = read_horses(save, "Horse")?.into_iter().map(some).collect();
+        if horses.len() != SLOTS + 1 {
+            return Err(format!(
+                "Error: expected {} Horse_ActorName entries, got {}",
+                SLOTS + 1,
+                horses.len()
+            ));
+        }
+        let wild = horses.pop().unwrap();
+        let causes = save
+            .get_s32s("DeadHorse_DeadCause")
+            .ok_or("Error: save has no DeadHorse_DeadCause".to_string())?;
+        let all = read_horses(save, "DeadHorse")?;
+        let dead_slots = all.len();
+        let dead = all
+            .into_iter()
+            .zip(causes)
+            .filter(|(h, _)| !h.actor.is_empty())
+            .map(|(horse, cause)| DeadHorse { horse, cause })
+            .collect();
+        Ok(Stable {
+            horses,
+            wild,
+            dead,
+            dead_slots,
+        })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        if self.horses.len() != SLOTS {
+            return Err(format!("Error: the stable has {} slots", SLOTS));
+        }
+        if self.dead.len() > self.dead_slots {
+            return Err(format!("Error: at most {} dead horses", self.dead_slots));
+        }
+        let mut horses: Vec<_> = self
+            .horses
+            .iter()
+            .chain(std::iter::once(&self.wild))
+            .map(|h| h.clone().unwrap_or_default())
+            .collect();
+        write_horses(save, "Horse", &horses)?;
+        horses = self.dead.iter().map(|d| d.horse.clone()).collect();
+        horses.resize(self.dead_slots, Horse::default());
+        write_horses(save, "DeadHorse", &horses)?;
+        let mut causes: Vec<_> = self.dead.iter().map(|d| d.cause).collect();
+        causes.resize(self.dead_slots, -1);
+        save.set_s32s("DeadHorse_DeadCause", &causes)
+    }
+
+    fn horse(&mut self, slot: usize) -> Result<&mut Horse, String> {
+        self.horses
+            .get_mut(slot)
+            .and_then(|h| h.as_mut())
+            .ok_or(format!("Error: no horse in stable slot {}", slot))
+    }
+
+    pub fn rename(&mut self, slot: usize, name: &str) -> Result<(), String> {
+        if name.is_empty() || name.len() >= 64 {
+            return Err(format!("Error: bad horse name \"{}\"", name));
+        }
+        self.horse(slot)?.name = name.to_string();
+        Ok(())
+    }
+
+    /// Set the bond, from 0 to `MAX_BOND`
+    pub fn set_bond(&mut self, slot: usize, familiarity: f32) -> Result<(), String> {
+        if !(0.0..=MAX_BOND).contains(&familiarity) {
+            return Err(format!(
+                "Error: bond {} is outside 0 to {}",
+                familiarity, MAX_BOND
+            ));
+        }
+        self.horse(slot)?.familiarity = familiarity;
+        Ok(())
+    }
+
+    /// Swap the saddle and reins, e.g. `GameRomHorseSaddle_05`
+    pub fn set_tack(&mut self, slot: usize, saddle: &str, reins: &str) -> Result<(), String> {
+        if !saddle.starts_with(SADDLE) || !reins.starts_with(REINS) {
+            return Err(format!("Error: bad tack {} {}", saddle, reins));
+        }
+        let h = self.horse(slot)?;
+        h.saddle = saddle.to_string();
+        h.reins = reins.to_string();
+        Ok(())
+    }
+
+    /// Move dead horse `index` back into the first free stable slot
+    pub fn revive(&mut self, index: usize) -> Result<usize, String> {
+        if index >= self.dead.len() {
+            return Err(format!("Error: no dead horse {}", index));
+        }
+        let slot = self
+            .horses
+            .iter()
+            .position(|h| h.is_none())
+            .ok_or("Error: the stable is full".to_string())?;
+        self.horses[slot] = Some(self.dead.remove(index).horse);
+        Ok(slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_and_revive() {
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        let mut st = Stable::read(&s).unwrap();
+        let h = st.horses[0].as_ref().unwrap();
+        assert_eq!(
+            (h.actor.as_str(), h.name.as_str()),
+            ("GameRomHorseZelda", "Arashi")
+        );
+        assert_eq!(st.horses.iter().flatten().count(), 1);
+        assert_eq!(st.wild.as_ref().unwrap().actor, "GameRomHorse22");
+        assert!(st.dead.is_empty());
+
+        // Pretend the wild horse was registered and died
+        st.dead.push(DeadHorse {
+            horse: st.wild.clone().unwrap(),
+            cause: 1,
+        });
+        st.write(&mut s).unwrap();
+        let mut st = Stable::read(&s).unwrap();
+        assert_eq!(st.dead.len(), 1);
+
+        assert!(st.rename(1, "Epona").is_err());
+        st.rename(0, "Epona").unwrap();
+        st.set_bond(0, 100.0).unwrap();
+        assert!(st.set_bond(0, 101.0).is_err());
+        st.set_tack(0, "GameRomHorseSaddle_05", "GameRomHorseReins_05")
+            .unwrap();
+        assert_eq!(st.revive(0), Ok(1));
+        st.write(&mut s).unwrap();
+
+        let st = Stable::read(&s).unwrap();
+        let h = st.horses[0].as_ref().unwrap();
+        assert_eq!((h.name.as_str(), h.familiarity), ("Epona", 100.0));
+        assert_eq!(h.saddle, "GameRomHorseSaddle_05");
+        assert_eq!(st.horses[1].as_ref().unwrap().ride_time, 28);
+        assert!(st.dead.is_empty());
+        assert_eq!(s.get_s32s("DeadHorse_DeadCause").unwrap(), vec![-1; 5]);
+    }
+}
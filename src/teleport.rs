@@ -0,0 +1,284 @@
+//! Move Link by rewriting where the save puts him on load
+//!
+//! `PlayerSavePos` is the position and `PlayerSavePosAngleYDegree` the
+//! facing.  `PlayerSavePosMapType` and `PlayerSavePosMapName` name the map
+//! it is in: `MainField` with the grid tile, e.g. `E-6`, or `CDungeon` with
+//! the shrine, e.g. `Dungeon018`.  The game loads the wrong map if they do
+//! not agree with the position.  The caption keeps the location shown on
+//! the load screen.
+//!
+//! The named destinations are where the game put Link in saves made there,
+//! shrine interiors at the spot Link enters them.  The game leaves the
+//! district of the load screen as it was inside a shrine.  There are no
+//! tower or stable positions in the table yet, they need positions taken
+//! from saves made there.
+
+use crate::folder::{Caption, Slot};
+use crate::map::tile_name;
+use crate::SaveData;
+
+pub const MAIN_FIELD: &str = "MainField";
+pub const SHRINE: &str = "CDungeon";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Destination {
+    pub map_type: String,
+    pub map_name: String,
+    pub pos: [f32; 3],
+    /// Facing in degrees, 0 is north
+    pub angle: f32,
+    /// Location message name for the caption, e.g. `TimesShrine`, None
+    /// keeps the current one
+    pub location: Option<String>,
+    /// District message name for the caption, None keeps the current one
+    pub district: Option<String>,
+}
+
+/// (location, map type, map name, position, angle, district)
+type Named = (
+    &'static str,
+    &'static str,
+    &'static str,
+    [f32; 3],
+    f32,
+    Option<&'static str>,
+);
+
+/// Named destinations, the shrines with the `CDungeon` map type go inside
+/// the shrine
+const DESTINATIONS: [Named; 10] = [
+    (
+        "StartPoint",
+        MAIN_FIELD,
+        "D-6",
+        [-1127.899, 237.397, 1911.075],
+        144.26,
+        Some("MapArea_CentralHyrule"),
+    ),
+    (
+        "TimesShrine",
+        MAIN_FIELD,
+        "E-6",
+        [-832.344, 221.041, 1970.166],
+        82.23,
+        Some("MapArea_CentralHyrule"),
+    ),
+    (
+        "HyruleCastle",
+        MAIN_FIELD,
+        "E-4",
+        [-342.251, 181.008, -805.659],
+        63.39,
+        Some("MapArea_CentralHyrule"),
+    ),
+    (
+        "HyruleCastle_Hall_0",
+        MAIN_FIELD,
+        "E-3",
+        [-231.125, 295.989, -1048.687],
+        134.97,
+        Some("MapArea_CentralHyrule"),
+    ),
+    (
+        "GameYabusame",
+        MAIN_FIELD,
+        "E-8",
+        [-209.82, 128.718, 3487.034],
+        -125.16,
+        Some("MapArea_FironeGrassland"),
+    ),
+    (
+        "Dungeon018",
+        SHRINE,
+        "Dungeon018",
+        [0.0, 10.0, -26.0],
+        0.0,
+        None,
+    ),
+    (
+        "Dungeon115",
+        SHRINE,
+        "Dungeon115",
+        [0.0, 12.0, 26.0],
+        180.0,
+        None,
+    ),
+    (
+        "Dungeon042",
+        SHRINE,
+        "Dungeon042",
+        [-26.0, 18.0, -6.0],
+        90.0,
+        None,
+    ),
+    (
+        "Dungeon062",
+        SHRINE,
+        "Dungeon062",
+        [-0.5, 9.97, 22.0],
+        180.0,
+        None,
+    ),
+    (
+        "Dungeon086",
+        SHRINE,
+        "Dungeon086",
+        [0.0, 20.0, 68.0],
+        180.0,
+        None,
+    ),
+];
+
+impl Destination {
+    /// A position on the main map, the map name is its grid tile
+    pub fn field(pos: [f32; 3]) -> Result<Destination, String> {
+        let map_name =
+            tile_name(pos[0], pos[2]).ok_or(format!("Error: {:?} is outside the map", pos))?;
+        Ok(Destination {
+            map_type: MAIN_FIELD.to_string(),
+            map_name,
+            pos,
+            angle: 0.0,
+            location: None,
+            district: None,
+        })
+    }
+
+    /// A position inside a shrine, e.g. `Dungeon018`
+    pub fn shrine(name: &str, pos: [f32; 3]) -> Result<Destination, String> {
+        if name
+            .strip_prefix("Dungeon")
+            .and_then(|n| n.parse::<u32>().ok())
+            .is_none()
+        {
+            return Err(format!("Error: {} is not a shrine", name));
+        }
+        Ok(Destination {
+            map_type: SHRINE.to_string(),
+            map_name: name.to_string(),
+            pos,
+            angle: 0.0,
+            location: Some(name.to_string()),
+            district: None,
+        })
+    }
+
+    /// A destination from the built-in table, by location name
+    pub fn named(name: &str) -> Result<Destination, String> {
+        let (location, map_type, map_name, pos, angle, district) = DESTINATIONS
+            .iter()
+            .find(|d| d.0.eq_ignore_ascii_case(name))
+            .ok_or(format!("Error: unknown destination {}", name))?;
+        Ok(Destination {
+            map_type: map_type.to_string(),
+            map_name: map_name.to_string(),
+            pos: *pos,
+            angle: *angle,
+            location: Some(location.to_string()),
+            district: district.map(|d| d.to_string()),
+        })
+    }
+
+    /// Names accepted by `named`
+    pub fn names() -> Vec<&'static str> {
+        DESTINATIONS.iter().map(|d| d.0).collect()
+    }
+
+    /// Raw coordinates `x,y,z` or a name from the built-in table
+    pub fn parse(s: &str) -> Result<Destination, String> {
+        let parts: Result<Vec<f32>, _> = s.split(',').map(|v| v.trim().parse()).collect();
+        match parts {
+            Ok(p) if p.len() == 3 => Destination::field([p[0], p[1], p[2]]),
+            _ => Destination::named(s),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.pos.iter().any(|v| !v.is_finite()) {
+            return Err(format!("Error: bad position {:?}", self.pos));
+        }
+        if self.map_type == MAIN_FIELD
+            && tile_name(self.pos[0], self.pos[2]) != Some(self.map_name.clone())
+        {
+            return Err(format!(
+                "Error: {:?} is not in map tile {}",
+                self.pos, self.map_name
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl SaveData {
+    /// Move Link to `dest`, keeping the map name and type in step
+    pub fn teleport(&mut self, dest: &Destination) -> Result<(), String> {
+        dest.validate()?;
+        self.set_f32s("PlayerSavePos", &dest.pos)?;
+        self.set_f32("PlayerSavePosAngleYDegree", dest.angle)?;
+        self.set_str("PlayerSavePosMapType", &dest.map_type)?;
+        self.set_str("PlayerSavePosMapName", &dest.map_name)
+    }
+}
+
+impl Caption {
+    /// Show the location of `dest` on the load screen when it is known
+    pub fn teleport(&mut self, dest: &Destination) -> Result<(), String> {
+        match &dest.location {
+            Some(location) => self.set_location(location, dest.district.as_deref()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Slot {
+    /// Teleport and update the load screen location
+    pub fn teleport(&mut self, dest: &Destination) -> Result<(), String> {
+        self.save.teleport(dest)?;
+        self.caption.teleport(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn teleport_slot() {
+        let mut slot = Slot::read(Path::new("t/+99 IST/5"), 5).unwrap();
+        assert!(Destination::field([6000.0, 0.0, 0.0]).is_err());
+        assert!(Destination::named("Nowhere").is_err());
+        assert!(Destination::shrine("Hateno", [0.0; 3]).is_err());
+        let mut bad = Destination::named("TimesShrine").unwrap();
+        bad.map_name = "A-1".to_string();
+        assert!(slot.teleport(&bad).is_err());
+        assert!(Destination::named("Dungeon039").is_err());
+
+        let mut d = Destination::parse("1722.1, 350.7, 2992.8").unwrap();
+        d.angle = 90.0;
+        slot.teleport(&d).unwrap();
+        assert_eq!(
+            slot.save.get("PlayerSavePosMapName"),
+            Ok(serde_json::json!("G-7"))
+        );
+        assert_eq!(slot.save.get_f32("PlayerSavePosAngleYDegree"), Some(90.0));
+        assert_eq!(slot.caption.location(), "StartPoint");
+
+        slot.teleport(&Destination::named("GameYabusame").unwrap())
+            .unwrap();
+        assert_eq!(slot.caption.district(), "MapArea_FironeGrassland");
+
+        slot.teleport(&Destination::named("dungeon018").unwrap())
+            .unwrap();
+        assert_eq!(
+            slot.save.get_vec3f("PlayerSavePos"),
+            Some([0.0, 10.0, -26.0])
+        );
+        assert_eq!(
+            slot.save.get("PlayerSavePosMapType"),
+            Ok(serde_json::json!("CDungeon"))
+        );
+        assert_eq!(slot.caption.location(), "Dungeon018");
+        assert_eq!(slot.caption.district(), "MapArea_FironeGrassland");
+    }
+}
@@ -0,0 +1,186 @@
+//! Sheikah Towers, `MapTower_01` to `MapTower_15`
+//!
+//! ```text
+//! MapTower_NN           activated, reveals the region on the map
+//! Location_MapTowerNN   s32, times the location name was shown
+//! MapTower_DemoFirst    first activation cutscene, the map app opens with it
+//! MapTower_07_Demo      the Great Plateau Tower rising from the ground
+//! ```
+//!
+//! Without the cutscene flags an activated tower leaves the map app closed
+//! or the Great Plateau Tower still buried, so they are set with it.
+
+use crate::SaveData;
+
+pub const TOTAL: u32 = 15;
+
+const DEMO_FIRST: &str = "MapTower_DemoFirst";
+const PLATEAU: u32 = 7;
+const PLATEAU_DEMO: &str = "MapTower_07_Demo";
+
+/// Region of each tower, `MapTower_01` first.  Saves confirm 07 and 11:
+/// the Hero's Path stops at the Great Plateau and Akkala Towers in the saves
+/// that activated them.  The others are not confirmed by a save yet.
+const REGIONS: [&str; TOTAL as usize] = [
+    "Hebra",
+    "Tabantha",
+    "Gerudo",
+    "Wasteland",
+    "Central",
+    "Lake",
+    "Great Plateau",
+    "Dueling Peaks",
+    "Hateno",
+    "Faron",
+    "Akkala",
+    "Woodland",
+    "Ridgeland",
+    "Eldin",
+    "Lanayru",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tower {
+    pub number: u32,
+    pub activated: bool,
+    pub visits: i32,
+}
+
+impl Tower {
+    /// Flag name, e.g. `MapTower_07`
+    pub fn name(&self) -> String {
+        format!("MapTower_{:02}", self.number)
+    }
+    pub fn region(&self) -> &'static str {
+        REGIONS[self.number as usize - 1]
+    }
+}
+
+/// Tower number from `MapTower_07`, `7`, `Great Plateau` or
+/// `Great Plateau Tower`
+pub fn number(name: &str) -> Option<u32> {
+    let n = name.trim();
+    let n = n.strip_suffix(" Tower").unwrap_or(n);
+    if let Some(i) = REGIONS.iter().position(|r| r.eq_ignore_ascii_case(n)) {
+        return Some(i as u32 + 1);
+    }
+    let n: u32 = n.strip_prefix("MapTower_").unwrap_or(n).parse().ok()?;
+    (1..=TOTAL).contains(&n).then_some(n)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Towers {
+    pub towers: Vec<Tower>,
+    /// First activation cutscene seen
+    pub demo_first: bool,
+    /// Great Plateau Tower risen
+    pub plateau_demo: bool,
+}
+
+impl Towers {
+    pub fn read(save: &SaveData) -> Result<Towers, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        let bool = |k: &str| save.get_bool(k).ok_or(missing(k));
+        let mut towers = vec![];
+        for number in 1..=TOTAL {
+            let key = format!("Location_MapTower{:02}", number);
+            let mut t = Tower {
+                number,
+                activated: false,
+                visits: save.get_s32(&key).ok_or(missing(&key))?,
+            };
+            t.activated = bool(&t.name())?;
+            towers.push(t);
+        }
+        Ok(Towers {
+            towers,
+            demo_first: bool(DEMO_FIRST)?,
+            plateau_demo: bool(PLATEAU_DEMO)?,
+        })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        for t in &self.towers {
+            save.set_bool(&t.name(), t.activated)?;
+            save.set_s32(&format!("Location_MapTower{:02}", t.number), t.visits)?;
+        }
+        save.set_bool(DEMO_FIRST, self.demo_first)?;
+        save.set_bool(PLATEAU_DEMO, self.plateau_demo)
+    }
+
+    /// Tower by flag name, number or region
+    pub fn get_mut(&mut self, name: &str) -> Result<&mut Tower, String> {
+        let n = number(name).ok_or(format!("Error: no tower {}", name))?;
+        Ok(&mut self.towers[n as usize - 1])
+    }
+
+    /// Activate a tower and the flags the map needs to show it
+    pub fn activate(&mut self, name: &str) -> Result<(), String> {
+        let t = self.get_mut(name)?;
+        t.activated = true;
+        t.visits = t.visits.max(1);
+        let plateau = t.number == PLATEAU;
+        self.demo_first = true;
+        self.plateau_demo |= plateau;
+        Ok(())
+    }
+
+    pub fn activate_all(&mut self) {
+        for n in 1..=TOTAL {
+            self.activate(&n.to_string()).unwrap();
+        }
+    }
+
+    pub fn activated(&self) -> usize {
+        self.towers.iter().filter(|t| t.activated).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activate_towers() {
+        let mut s = SaveData::read("t/+99 IST/0/game_data.sav").unwrap();
+        let mut t = Towers::read(&s).unwrap();
+        assert_eq!(t.activated(), 0);
+        assert!(!t.demo_first && !t.plateau_demo);
+        let done = Towers::read(&SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap()).unwrap();
+        assert_eq!(done.activated(), 15);
+        assert!(done.demo_first && done.plateau_demo);
+        // Activated on the way from the Great Plateau through Akkala
+        let route = Towers::read(&SaveData::read("t/+99 IST/6/game_data.sav").unwrap()).unwrap();
+        let regions: Vec<_> = route
+            .towers
+            .iter()
+            .filter(|t| t.activated)
+            .map(|t| (t.name(), t.region()))
+            .collect();
+        assert_eq!(
+            regions,
+            [
+                ("MapTower_07".to_string(), "Great Plateau"),
+                ("MapTower_11".to_string(), "Akkala")
+            ]
+        );
+
+        assert_eq!(number("Hateno Tower"), Some(9));
+        assert_eq!(number("MapTower_15"), Some(15));
+        assert!(t.activate("16").is_err());
+        assert!(t.activate("Hyrule Castle").is_err());
+        t.activate("Akkala").unwrap();
+        t.write(&mut s).unwrap();
+        assert_eq!(s.get_bool("MapTower_11"), Some(true));
+        assert_eq!(s.get_s32("Location_MapTower11"), Some(1));
+        assert_eq!(s.get_bool("MapTower_DemoFirst"), Some(true));
+        assert_eq!(s.get_bool("MapTower_07_Demo"), Some(false));
+
+        t.activate_all();
+        t.write(&mut s).unwrap();
+        let t = Towers::read(&s).unwrap();
+        assert_eq!(t.activated(), 15);
+        assert!(t.plateau_demo);
+        assert_eq!(t.towers[6].region(), "Great Plateau");
+    }
+}
@@ -0,0 +1,385 @@
+//! Hero's Path tracker blocks (`tracker/trackblockNN.sav` and
+//! `tracker/trackblock_hardNN.sav`)
+//!
+//! Each block is 64 KiB: a 0x40 byte header followed by chunks of
+//! 0x4c0 bytes.  A chunk starts with the save's play time and the total
+//! play time (seconds) and holds up to 300 packed positions:
+//!
+//! ```text
+//!  bits  0..11  |z|       bit 12  z < 0
+//!  bits 13..25  |x|       bit 26  x < 0
+//!  bits 27..30  flags     bit 31  on the MainField
+//! ```
+//!
+//! Coordinates are whole meters in the same space as `PlayerSavePos`.
+//! Positions recorded inside shrines are in the shrine's local space
+//! and are excluded from the map exports.
+
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+use crate::read_u32;
+
+const HEADER_SIZE: usize = 0x40;
+const CHUNK_SIZE: usize = 0x4c0;
+const CHUNK_POINTS: usize = 300;
+const MAX_CHUNKS: usize = 48;
+/// Written when the path is interrupted, e.g. after warping
+const BREAK: u32 = 0xfbff_dfff;
+/// Nominal time between two recorded positions
+const SAMPLE_INTERVAL: f64 = 2.0;
+/// GPX requires WGS84 ranges; map meters are divided by this value
+pub const GPX_SCALE: f64 = 100.0;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Master,
+}
+
+impl Mode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Normal => "normal",
+            Mode::Master => "master",
+        }
+    }
+    fn prefix(&self) -> &'static str {
+        match self {
+            Mode::Normal => "trackblock",
+            Mode::Master => "trackblock_hard",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrackPoint {
+    /// Play time in seconds, interpolated within the chunk
+    pub time: f64,
+    pub x: i32,
+    pub z: i32,
+    /// Position is on the MainField, otherwise it is shrine-local
+    pub field: bool,
+    /// Unidentified state bits 27..30
+    pub flags: u8,
+}
+
+impl TrackPoint {
+    fn decode(v: u32, time: f64) -> Option<TrackPoint> {
+        if v == BREAK {
+            return None;
+        }
+        let mut z = (v & 0xfff) as i32;
+        if v & (1 << 12) != 0 {
+            z = -z;
+        }
+        let mut x = ((v >> 13) & 0x1fff) as i32;
+        if v & (1 << 26) != 0 {
+            x = -x;
+        }
+        Some(TrackPoint {
+            time,
+            x,
+            z,
+            field: v & (1 << 31) != 0,
+            flags: ((v >> 27) & 0xf) as u8,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TrackChunk {
+    pub play_time: u32,
+    pub total_time: u32,
+    /// `None` marks a break in the path
+    pub points: Vec<Option<TrackPoint>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TrackBlock {
+    pub index: u8,
+    pub chunks: Vec<TrackChunk>,
+    data: Vec<u8>,
+}
+
+impl TrackBlock {
+    pub fn new(data: &[u8]) -> Result<TrackBlock, i32> {
+        if data.len() < HEADER_SIZE {
+            return Err(1);
+        }
+        let index = data[0];
+        let full = u16::from_le_bytes([data[2], data[3]]) as usize;
+        let last = read_u32(data, 4)? as usize;
+        if full >= MAX_CHUNKS || last > CHUNK_POINTS {
+            return Err(2);
+        }
+        // Full chunks, then the header and points of the last one
+        if data.len() < HEADER_SIZE + full * CHUNK_SIZE + 16 + last * 4 {
+            return Err(3);
+        }
+        let mut chunks = vec![];
+        for i in 0..=full {
+            let off = HEADER_SIZE + i * CHUNK_SIZE;
+            let n = if i < full { CHUNK_POINTS } else { last };
+            let play_time = read_u32(data, off)?;
+            let total_time = read_u32(data, off + 4)?;
+            let mut raw = vec![];
+            for j in 0..n {
+                raw.push(read_u32(data, off + 16 + j * 4)?);
+            }
+            chunks.push((play_time, total_time, raw));
+        }
+        let mut out = vec![];
+        for (i, (play_time, total_time, raw)) in chunks.iter().enumerate() {
+            // Spread the points evenly up to the start of the next chunk
+            let step = match chunks.get(i + 1) {
+                Some((next, _, _)) if *next > *play_time && !raw.is_empty() => {
+                    (*next - *play_time) as f64 / raw.len() as f64
+                }
+                _ => SAMPLE_INTERVAL,
+            };
+            let points = raw
+                .iter()
+                .enumerate()
+                .map(|(j, &v)| TrackPoint::decode(v, *play_time as f64 + step * j as f64))
+                .collect();
+            out.push(TrackChunk {
+                play_time: *play_time,
+                total_time: *total_time,
+                points,
+            });
+        }
+        Ok(TrackBlock {
+            index,
+            chunks: out,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn read(filename: &str) -> Result<TrackBlock, i32> {
+        let mut data = vec![];
+        File::open(filename)
+            .and_then(|mut fp| fp.read_to_end(&mut data))
+            .or(Err(1))?;
+        TrackBlock::new(&data)
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Option<TrackPoint>> {
+        self.chunks.iter().flat_map(|c| c.points.iter())
+    }
+}
+
+/// All Hero's Path blocks of a save folder
+#[derive(Clone, Debug, Default)]
+pub struct Tracker {
+    pub normal: Vec<TrackBlock>,
+    pub master: Vec<TrackBlock>,
+}
+
+impl Tracker {
+    /// Read every block from a `tracker` directory
+    pub fn read(dir: &str) -> Result<Tracker, String> {
+        let mut t = Tracker::default();
+        for mode in [Mode::Normal, Mode::Master] {
+            for i in 0.. {
+                let path = Path::new(dir).join(format!("{}{:02}.sav", mode.prefix(), i));
+                if !path.exists() {
+                    break;
+                }
+                let name = path.to_string_lossy();
+                let block = TrackBlock::read(&name)
+                    .map_err(|e| format!("Error: could not read {}: {}", name, e))?;
+                match mode {
+                    Mode::Normal => t.normal.push(block),
+                    Mode::Master => t.master.push(block),
+                }
+            }
+        }
+        Ok(t)
+    }
+
+    /// Write every block back to a `tracker` directory
+    pub fn write(&self, dir: &str) -> Result<(), String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Error: {}: {}", dir, e))?;
+        for mode in [Mode::Normal, Mode::Master] {
+            for (i, block) in self.blocks(mode).iter().enumerate() {
+                let path = Path::new(dir).join(format!("{}{:02}.sav", mode.prefix(), i));
+                std::fs::write(&path, block.data())
+                    .map_err(|e| format!("Error: {}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn blocks(&self, mode: Mode) -> &[TrackBlock] {
+        match mode {
+            Mode::Normal => &self.normal,
+            Mode::Master => &self.master,
+        }
+    }
+
+    /// Continuous runs of MainField positions
+    pub fn segments(&self, mode: Mode) -> Vec<Vec<TrackPoint>> {
+        let mut out = vec![];
+        let mut cur: Vec<TrackPoint> = vec![];
+        for p in self.blocks(mode).iter().flat_map(|b| b.points()) {
+            match p {
+                Some(p) if p.field => cur.push(*p),
+                _ => {
+                    if !cur.is_empty() {
+                        out.push(std::mem::take(&mut cur));
+                    }
+                }
+            }
+        }
+        if !cur.is_empty() {
+            out.push(cur);
+        }
+        out
+    }
+
+    /// FeatureCollection with one LineString per segment, tagged by mode
+    pub fn geojson(&self) -> Value {
+        let mut features = vec![];
+        for mode in [Mode::Normal, Mode::Master] {
+            for (i, seg) in self.segments(mode).iter().enumerate() {
+                // A LineString needs at least two positions
+                if seg.len() < 2 {
+                    continue;
+                }
+                let coords: Vec<[i32; 2]> = seg.iter().map(|p| [p.x, p.z]).collect();
+                features.push(json!({
+                    "type": "Feature",
+                    "geometry": { "type": "LineString", "coordinates": coords },
+                    "properties": {
+                        "mode": mode.name(),
+                        "segment": i,
+                        "start_time": seg[0].time,
+                        "end_time": seg[seg.len() - 1].time,
+                    },
+                }));
+            }
+        }
+        json!({ "type": "FeatureCollection", "features": features })
+    }
+
+    /// One track per mode, one track segment per path segment
+    ///
+    /// Coordinates are `lon = x / GPX_SCALE` and `lat = -z / GPX_SCALE`
+    /// so north stays up, times are play time since the epoch.
+    pub fn gpx(&self) -> String {
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        s.push_str("<gpx version=\"1.1\" creator=\"botw-editor\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n");
+        for mode in [Mode::Normal, Mode::Master] {
+            let segs = self.segments(mode);
+            if segs.is_empty() {
+                continue;
+            }
+            let _ = writeln!(s, "  <trk>\n    <name>{}</name>", mode.name());
+            for seg in segs {
+                s.push_str("    <trkseg>\n");
+                for p in seg {
+                    let _ = writeln!(
+                        s,
+                        "      <trkpt lat=\"{:.4}\" lon=\"{:.4}\"><time>{}</time></trkpt>",
+                        -p.z as f64 / GPX_SCALE,
+                        p.x as f64 / GPX_SCALE,
+                        iso8601(p.time as u64)
+                    );
+                }
+                s.push_str("    </trkseg>\n");
+            }
+            s.push_str("  </trk>\n");
+        }
+        s.push_str("</gpx>\n");
+        s
+    }
+
+    /// `mode,segment,time,x,z,flags` with every MainField position
+    pub fn csv(&self) -> String {
+        let mut s = String::from("mode,segment,time,x,z,flags\n");
+        for mode in [Mode::Normal, Mode::Master] {
+            for (i, seg) in self.segments(mode).iter().enumerate() {
+                for p in seg {
+                    let _ = writeln!(
+                        s,
+                        "{},{},{:.1},{},{},{}",
+                        mode.name(),
+                        i,
+                        p.time,
+                        p.x,
+                        p.z,
+                        p.flags
+                    );
+                }
+            }
+        }
+        s
+    }
+}
+
+// Seconds since the epoch as `YYYY-MM-DDThh:mm:ssZ`
+fn iso8601(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    // Civil from days, Howard Hinnant
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        y,
+        m,
+        d,
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_point_matches_save_pos() {
+        let t = Tracker::read("t/+99 cleanup/tracker").unwrap();
+        assert_eq!(t.normal.len(), 4);
+        assert_eq!(t.master.len(), 1);
+        // Slot 1 was saved at the end of trackblock03 at [-1094.9, 22.1, -2653.4]
+        let p = t.normal[3].points().last().unwrap().unwrap();
+        assert!(p.field);
+        assert_eq!((p.x, p.z), (-1095, -2653));
+
+        // Truncated blocks are errors, not panics
+        let mut short = t.normal[3].data()[..HEADER_SIZE].to_vec();
+        short[2..8].copy_from_slice(&[0, 0, 1, 0, 0, 0]);
+        assert_eq!(TrackBlock::new(&short).unwrap_err(), 3);
+        let mut short = t.normal[3].data()[..HEADER_SIZE + CHUNK_SIZE].to_vec();
+        short[2..8].copy_from_slice(&[1, 0, 44, 1, 0, 0]);
+        assert_eq!(TrackBlock::new(&short).unwrap_err(), 3);
+
+        let gj = t.geojson();
+        let features = gj["features"].as_array().unwrap();
+        assert!(features.iter().any(|f| f["properties"]["mode"] == "master"));
+        assert!(t
+            .csv()
+            .starts_with("mode,segment,time,x,z,flags\nnormal,0,"));
+        assert!(t.gpx().contains("<name>master</name>"));
+        assert_eq!(iso8601(59623), "1970-01-01T16:33:43Z");
+    }
+}
@@ -0,0 +1,224 @@
+//! Weather forecast, the `climateWeather*` arrays
+//!
+//! Each array holds one s32 per climate zone: `climateWeather` for today,
+//! `climateWeather2` and `climateWeather3` for the next two days.  A value
+//! packs six 4-bit weathers, one per four hours from midnight, lowest
+//! nibble first.
+
+use std::fmt;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::SaveData;
+
+const KEYS: [&str; 3] = ["climateWeather", "climateWeather2", "climateWeather3"];
+pub const DAYS: usize = 3;
+/// Four hour slots in a day
+pub const SLOTS_PER_DAY: usize = 6;
+/// Slots across all three days, `day * SLOTS_PER_DAY + slot`
+pub const DAY_SLOTS: usize = DAYS * SLOTS_PER_DAY;
+
+/// Climate zones in `climateWeather` order
+pub const ZONES: [&str; 20] = [
+    "HyrulePlainClimate",
+    "NorthHyrulePlainClimate",
+    "HebraFrostClimate",
+    "TabantaAridClimate",
+    "FrostClimate",
+    "GerudoDesertClimate",
+    "GerudoPlateauClimate",
+    "EldinClimateLv0",
+    "TamourPlainClimate",
+    "ZoraTemperateClimate",
+    "HateruPlainClimate",
+    "FiloneSubtropicalClimate",
+    "SouthHateruHumidTemperateClimate",
+    "EldinClimateLv1",
+    "EldinClimateLv2",
+    "DarkWoodsClimate",
+    "LostWoodClimate",
+    "GerudoFrostClimate",
+    "KorogForest",
+    "GerudoDesertClimateLv2",
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Weather {
+    Sun,
+    Cloudy,
+    Rain,
+    HeavyRain,
+    Snow,
+    HeavySnow,
+    ThunderStorm,
+    ThunderRain,
+    BlueSkyRain,
+}
+
+const WEATHERS: [(Weather, u32, &str); 9] = [
+    (Weather::Sun, 0, "sun"),
+    (Weather::Cloudy, 1, "cloudy"),
+    (Weather::Rain, 2, "rain"),
+    (Weather::HeavyRain, 3, "heavy_rain"),
+    (Weather::Snow, 4, "snow"),
+    (Weather::HeavySnow, 5, "heavy_snow"),
+    (Weather::ThunderStorm, 6, "thunder_storm"),
+    (Weather::ThunderRain, 7, "thunder_rain"),
+    (Weather::BlueSkyRain, 8, "blue_sky_rain"),
+];
+
+impl Weather {
+    pub fn id(&self) -> u32 {
+        WEATHERS.iter().find(|w| w.0 == *self).unwrap().1
+    }
+    pub fn from_id(id: u32) -> Option<Weather> {
+        WEATHERS.iter().find(|w| w.1 == id).map(|w| w.0)
+    }
+    pub fn name(&self) -> &'static str {
+        WEATHERS.iter().find(|w| w.0 == *self).unwrap().2
+    }
+    pub fn from_name(name: &str) -> Option<Weather> {
+        WEATHERS
+            .iter()
+            .find(|w| w.2.eq_ignore_ascii_case(name))
+            .map(|w| w.0)
+    }
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Index of a climate zone by name
+pub fn zone(name: &str) -> Option<usize> {
+    ZONES.iter().position(|z| z.eq_ignore_ascii_case(name))
+}
+
+/// The six weathers packed in one value, None for values the game does not use
+pub fn decode(value: i32) -> [Option<Weather>; SLOTS_PER_DAY] {
+    std::array::from_fn(|i| Weather::from_id((value as u32 >> (i * 4)) & 0xF))
+}
+
+fn check(zone: usize, day_slot: usize) -> Result<(), String> {
+    if zone >= ZONES.len() {
+        return Err(format!("Error: no climate zone {}", zone));
+    }
+    if day_slot >= DAY_SLOTS {
+        return Err(format!(
+            "Error: day slot {} is outside 0 to {}",
+            day_slot,
+            DAY_SLOTS - 1
+        ));
+    }
+    Ok(())
+}
+
+impl SaveData {
+    fn climate(&self, day: usize) -> Result<Vec<i32>, String> {
+        let v = self
+            .get_s32s(KEYS[day])
+            .ok_or(format!("Error: save has no {}", KEYS[day]))?;
+        if v.len() != ZONES.len() {
+            return Err(format!(
+                "Error: expected {} {} entries, got {}",
+                ZONES.len(),
+                KEYS[day],
+                v.len()
+            ));
+        }
+        Ok(v)
+    }
+
+    /// Weather of `zone` for every slot of the three days
+    pub fn forecast(&self, zone: usize) -> Result<Vec<Option<Weather>>, String> {
+        check(zone, 0)?;
+        let mut out = vec![];
+        for day in 0..DAYS {
+            out.extend(decode(self.climate(day)?[zone]));
+        }
+        Ok(out)
+    }
+
+    pub fn weather(&self, zone: usize, day_slot: usize) -> Result<Option<Weather>, String> {
+        check(zone, day_slot)?;
+        Ok(self.forecast(zone)?[day_slot])
+    }
+
+    /// Set the weather of `zone` for slot `day_slot` of the three days
+    pub fn set_weather(
+        &mut self,
+        zone: usize,
+        day_slot: usize,
+        weather: Weather,
+    ) -> Result<(), String> {
+        check(zone, day_slot)?;
+        let day = day_slot / SLOTS_PER_DAY;
+        let shift = (day_slot % SLOTS_PER_DAY) * 4;
+        let mut v = self.climate(day)?;
+        let packed = (v[zone] as u32 & !(0xF << shift)) | (weather.id() << shift);
+        v[zone] = packed as i32;
+        self.set_s32s(KEYS[day], &v)
+    }
+
+    fn forecast_value(&self) -> serde_json::Value {
+        let zones: Vec<_> = ZONES
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                let w: Vec<_> = self
+                    .forecast(i)
+                    .ok()?
+                    .iter()
+                    .map(|w| w.map(|w| w.name()))
+                    .collect();
+                Some(serde_json::json!({ "zone": name, "weather": w }))
+            })
+            .collect();
+        serde_json::json!(zones)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl SaveData {
+    /// Forecast of every zone, `[{zone, weather: [name or null; 18]}]`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn weather_json(&self) -> serde_json::Value {
+        self.forecast_value()
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn weather_json(&self) -> JsValue {
+        json!(&self.forecast_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_and_set_weather() {
+        let mut s = SaveData::read("t/+99 IST/5/game_data.sav").unwrap();
+        let f = s.forecast(1).unwrap();
+        assert_eq!(f.len(), DAY_SLOTS);
+        // 0x101100 today, 0x11101 tomorrow
+        assert_eq!(f[2], Some(Weather::Cloudy));
+        assert_eq!(f[4], Some(Weather::Sun));
+        assert_eq!(f[6], Some(Weather::Cloudy));
+        assert_eq!(decode(0x9f)[..2], [None, None]);
+        assert!(s.weather(20, 0).is_err());
+        assert!(s.set_weather(0, DAY_SLOTS, Weather::Sun).is_err());
+
+        let z = zone("GerudoDesertClimate").unwrap();
+        s.set_weather(z, 13, Weather::ThunderStorm).unwrap();
+        s.set_weather(1, 2, Weather::Sun).unwrap();
+        assert_eq!(s.weather(z, 13), Ok(Some(Weather::ThunderStorm)));
+        assert_eq!(s.get_s32s("climateWeather3").unwrap()[z], 0x60);
+        assert_eq!(s.get_s32s("climateWeather").unwrap()[1], 0x101000);
+        let j = s.weather_json();
+        assert_eq!(j[z]["weather"][13], "thunder_storm");
+        assert_eq!(j[0]["zone"], "HyrulePlainClimate");
+    }
+}
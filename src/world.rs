@@ -0,0 +1,228 @@
+//! Time of day and the Blood Moon, the `WM_*` world manager values
+//!
+//! `WM_Time` is the sun angle in degrees, 15 per hour from midnight, so a
+//! new game's 78.75 is 5:15.  `WM_BloodyMoonTimer` counts the same
+//! degrees, 360 a day, since the last Blood Moon or the start of the game;
+//! one is due when it passes a week.  The game works out the day and night
+//! flags from the time on load.
+//!
+//! A Blood Moon also clears the `MainField_<Actor>_<hash id>` and
+//! `AocField_*` flags of actors that respawn, such as defeated enemies,
+//! picked up items, mined ore and the chests of enemy camps, which are
+//! refilled.  The game keeps this per flag in its own flag data, which is
+//! not in the save.  `RESETS` guesses it from the actor's `Class`, so
+//! `approximate_blood_moon` can clear flags the game keeps or miss some it
+//! resets.
+
+use std::sync::OnceLock;
+
+use wildmatch::WildMatch;
+
+use crate::actor::{self, Class, Map};
+use crate::SaveData;
+
+const TIME: &str = "WM_Time";
+const BLOOD_MOON_TIMER: &str = "WM_BloodyMoonTimer";
+const BLOODY_DAY: &str = "WM_BloodyDay";
+
+const DEGREES_PER_HOUR: f32 = 15.0;
+/// Degrees in an in-game day
+pub const DAY: f32 = 24.0 * DEGREES_PER_HOUR;
+/// Time between Blood Moons
+pub const BLOOD_MOON_INTERVAL: f32 = 7.0 * DAY;
+
+const RESET_MAPS: [Map; 2] = [Map::MainField, Map::AocField];
+
+/// Actor classes a Blood Moon likely resets
+const RESETS: [Class; 8] = [
+    Class::Enemy,
+    Class::Weapon,
+    Class::Material,
+    Class::Animal,
+    Class::OreDeposit,
+    Class::Arrow,
+    Class::Crate,
+    Class::Barrel,
+];
+
+/// Actors that go against their class, checked before `RESETS`
+const RESET_EXCEPTIONS: [(&str, bool); 3] = [
+    // Yiga ambushes stay defeated
+    ("Enemy_Assassin*", false),
+    // Master Sword
+    ("Weapon_Sword_070", false),
+    // Enemy camp chests
+    ("TBox_Field_Enemy", true),
+];
+
+/// Whether a Blood Moon likely resets the flags of `actor`, e.g.
+/// `Enemy_Lynel_Junior`, guessed from the name
+pub fn resets_on_blood_moon(actor: &str) -> bool {
+    static PATTERNS: OnceLock<Vec<(WildMatch, bool)>> = OnceLock::new();
+    let exception = PATTERNS
+        .get_or_init(|| {
+            RESET_EXCEPTIONS
+                .iter()
+                .map(|(pattern, reset)| (WildMatch::new(pattern), *reset))
+                .collect()
+        })
+        .iter()
+        .find(|(re, _)| re.matches(actor));
+    match exception {
+        Some((_, reset)) => *reset,
+        None => Class::of_actor(actor).is_some_and(|c| RESETS.contains(&c)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct World {
+    /// Degrees, 0 to 360
+    pub time: f32,
+    /// Degrees since the last Blood Moon, 360 a day
+    pub blood_moon_timer: f32,
+    /// Tonight is a Blood Moon
+    pub bloody_day: bool,
+}
+
+impl World {
+    pub fn read(save: &SaveData) -> Result<World, String> {
+        let missing = |k: &str| format!("Error: save has no {}", k);
+        Ok(World {
+            time: save.get_f32(TIME).ok_or(missing(TIME))?,
+            blood_moon_timer: save
+                .get_f32(BLOOD_MOON_TIMER)
+                .ok_or(missing(BLOOD_MOON_TIMER))?,
+            bloody_day: save.get_bool(BLOODY_DAY).ok_or(missing(BLOODY_DAY))?,
+        })
+    }
+
+    pub fn write(&self, save: &mut SaveData) -> Result<(), String> {
+        save.set_f32(TIME, self.time)?;
+        save.set_f32(BLOOD_MOON_TIMER, self.blood_moon_timer)?;
+        save.set_bool(BLOODY_DAY, self.bloody_day)
+    }
+
+    /// Hours and minutes, e.g. (5, 15)
+    pub fn time_of_day(&self) -> (u32, u32) {
+        let minutes = (self.time.rem_euclid(360.0) / DEGREES_PER_HOUR * 60.0) as u32;
+        (minutes / 60 % 24, minutes % 60)
+    }
+
+    pub fn set_time_of_day(&mut self, hh: u32, mm: u32) -> Result<(), String> {
+        if hh >= 24 || mm >= 60 {
+            return Err(format!("Error: bad time of day {:02}:{:02}", hh, mm));
+        }
+        self.time = (hh as f32 + mm as f32 / 60.0) * DEGREES_PER_HOUR;
+        Ok(())
+    }
+
+    /// Degrees until a Blood Moon is due, 0 when it is overdue
+    pub fn blood_moon_in(&self) -> f32 {
+        (BLOOD_MOON_INTERVAL - self.blood_moon_timer).max(0.0)
+    }
+
+    /// Make a Blood Moon due, it rises the next night
+    pub fn force_blood_moon(&mut self) {
+        self.blood_moon_timer = BLOOD_MOON_INTERVAL;
+    }
+
+    /// Start the week over and cancel tonight's Blood Moon
+    pub fn postpone_blood_moon(&mut self) {
+        self.blood_moon_timer = 0.0;
+        self.bloody_day = false;
+    }
+}
+
+impl SaveData {
+    /// Clear the actor flags `resets_on_blood_moon` picks and start a new
+    /// week, returns the number of flags cleared.  An approximation of a
+    /// Blood Moon, see the module docs
+    pub fn approximate_blood_moon(&mut self) -> Result<usize, String> {
+        let mut cleared = 0;
+        for f in actor::flags() {
+            if !RESET_MAPS.contains(&f.map) || !resets_on_blood_moon(&f.actor) {
+                continue;
+            }
+            let key = f.flag();
+            if self.get_bool(&key) == Some(true) {
+                self.set_bool(&key, false)?;
+                cleared += 1;
+            }
+        }
+        let mut world = World::read(self)?;
+        world.postpone_blood_moon();
+        world.write(self)?;
+        Ok(cleared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn night_blood_moon_imminent() {
+        let mut s = SaveData::read("t/+99 cleanup/5/game_data.sav").unwrap();
+        let mut w = World::read(&s).unwrap();
+        assert_eq!(w.time_of_day(), (23, 58));
+        assert_eq!(
+            World::read(&SaveData::read("t/+99 IST/5/game_data.sav").unwrap())
+                .unwrap()
+                .time_of_day(),
+            (5, 15)
+        );
+        assert!(w.blood_moon_in() > 0.0);
+        // The timer runs with the clock from the new game's 5:15
+        for slot in [0, 1] {
+            let s = SaveData::read(&format!("t/+99 IST/{}/game_data.sav", slot)).unwrap();
+            let w = World::read(&s).unwrap();
+            assert!((w.blood_moon_timer - (w.time - 78.75)).abs() < 0.01);
+            assert_eq!(w.time_of_day().0, 6);
+        }
+
+        assert!(w.set_time_of_day(24, 0).is_err());
+        assert!(w.set_time_of_day(12, 60).is_err());
+        w.set_time_of_day(21, 30).unwrap();
+        w.force_blood_moon();
+        w.write(&mut s).unwrap();
+
+        let mut w = World::read(&s).unwrap();
+        assert_eq!(s.get_f32("WM_Time"), Some(322.5));
+        assert_eq!(w.time_of_day(), (21, 30));
+        assert_eq!(w.blood_moon_in(), 0.0);
+        w.bloody_day = true;
+        w.postpone_blood_moon();
+        w.write(&mut s).unwrap();
+        assert_eq!(
+            World::read(&s).unwrap().blood_moon_in(),
+            BLOOD_MOON_INTERVAL
+        );
+        assert_eq!(s.get_bool("WM_BloodyDay"), Some(false));
+    }
+
+    #[test]
+    fn blood_moon_respawns() {
+        let mut s = SaveData::read("t/+99 cleanup/0/game_data.sav").unwrap();
+        assert!(resets_on_blood_moon("Enemy_Lynel_Junior"));
+        assert!(!resets_on_blood_moon("TBox_Field_Stone"));
+        assert!(resets_on_blood_moon("Enemy_Bokoblin_Guard_Junior"));
+        assert!(resets_on_blood_moon("Enemy_Guardian_A_Fixed_Moss"));
+        assert!(resets_on_blood_moon("TBox_Field_Enemy"));
+        assert!(!resets_on_blood_moon("Enemy_Assassin_Junior"));
+        assert!(!resets_on_blood_moon("Npc_HiddenKorokGround"));
+        assert_eq!(s.approximate_blood_moon(), Ok(109));
+        for (key, value) in [
+            ("MainField_Enemy_Giant_Junior_3337886442", false),
+            ("MainField_Obj_Mineral_C_01_597319366", false),
+            ("MainField_Weapon_Shield_040_394653421", false),
+            ("MainField_Enemy_Bokoblin_Guard_Junior_185019670", false),
+            ("MainField_TBox_Field_Enemy_999953490", false),
+            ("MainField_Weapon_Sword_070_2427157272", true),
+            ("MainField_TBox_Field_Stone_4129291175", true),
+        ] {
+            assert_eq!(s.get_bool(key), Some(value), "{}", key);
+        }
+        assert_eq!(World::read(&s).unwrap().blood_moon_timer, 0.0);
+        assert_eq!(s.approximate_blood_moon(), Ok(0));
+    }
+}